@@ -19,4 +19,129 @@ pub struct TileMod {
     pub id: String,
     pub name: String,
     pub texture_path: String,
+    /// Damage dealt to players standing on the tile.
+    #[serde(default)]
+    pub damage: i32,
+    /// Players and mobs can't walk onto the tile, like a wall.
+    #[serde(default)]
+    pub solid: bool,
+    /// Light emitted by the tile, from 0 for none to 15, like lava.
+    #[serde(default)]
+    pub light: u8,
+    /// Frames side by side in the texture, played in a loop like water.
+    #[serde(default = "default_count")]
+    pub frames: u32,
+    /// Seconds each frame is shown.
+    #[serde(default = "default_frame_time")]
+    pub frame_time: f32,
 }   
+
+/// Crafting recipe. Items are referred to by id, e.g. `wood`, `hoe`,
+/// `block:tree` or `tile:sand`.
+#[derive(Debug, Deserialize, Clone)]
+pub struct RecipeMod {
+    pub id: String,
+    pub ingredients: Vec<RecipeItem>,
+    pub result: RecipeItem,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RecipeItem {
+    pub item: String,
+    #[serde(default = "default_count")]
+    pub count: u32,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+fn default_frame_time() -> f32 {
+    0.25
+}
+
+/// Burst of particles played when something happens in the world.
+#[derive(Debug, Deserialize, Clone)]
+pub struct EmitterMod {
+    pub id: String,
+    /// `step:<tile>` for footsteps on a tile or `hit:<block>` for hitting a
+    /// block, e.g. `step:sand` or `hit:tree`.
+    pub trigger: String,
+    /// Particles per burst.
+    #[serde(default = "default_count")]
+    pub count: u32,
+    /// Seconds each particle lives.
+    pub lifetime: f32,
+    /// Slowest and fastest starting speed in pixels per second.
+    pub speed: (f32, f32),
+    /// Degrees, 0 is right and -90 is up.
+    #[serde(default = "default_emitter_direction")]
+    pub direction: f32,
+    /// Degrees the direction varies by in total, 360 for all around.
+    #[serde(default = "default_emitter_spread")]
+    pub spread: f32,
+    /// Downwards acceleration in pixels per second squared.
+    #[serde(default)]
+    pub gravity: f32,
+    #[serde(default = "default_particle_size")]
+    pub size: f32,
+    /// RGBA color. Particles fade out over their lifetime.
+    pub color: [f32; 4],
+}
+
+fn default_emitter_direction() -> f32 {
+    -90.0
+}
+
+fn default_emitter_spread() -> f32 {
+    360.0
+}
+
+fn default_particle_size() -> f32 {
+    2.0
+}
+
+/// Creature that spawns by itself. Passive mobs wander around, hostile
+/// ones chase and hurt players.
+#[derive(Debug, Deserialize, Clone)]
+pub struct MobMod {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub hostile: bool,
+    /// Biomes the mob spawns in, e.g. `Plains` or a custom biome id. Any
+    /// biome if empty.
+    #[serde(default)]
+    pub biomes: Vec<String>,
+    /// Spawns only at night and disappears at dawn. Other mobs spawn
+    /// during the day.
+    #[serde(default)]
+    pub night: bool,
+    pub hp: i32,
+    pub speed: f32,
+    #[serde(default = "default_mob_size")]
+    pub size: f32,
+    /// Damage dealt to players touching the mob.
+    #[serde(default)]
+    pub damage: i32,
+    /// Chance to be picked relative to the other mobs that can spawn.
+    #[serde(default = "default_count")]
+    pub weight: u32,
+    /// Sprite sheet with `frames` walking frames side by side.
+    pub texture_path: Option<String>,
+    #[serde(default = "default_count")]
+    pub frames: u32,
+    /// RGBA color drawn when there is no texture.
+    #[serde(default = "default_mob_color")]
+    pub color: [f32; 4],
+    #[serde(default)]
+    pub drops: Vec<RecipeItem>,
+}
+
+fn default_mob_size() -> f32 {
+    24.0
+}
+
+fn default_mob_color() -> [f32; 4] {
+    [1.0, 1.0, 1.0, 1.0]
+}
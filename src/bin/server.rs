@@ -0,0 +1,25 @@
+use game_lib::{server::server::{Server, ServerConfig}, utils::mod_loader::ModLoader};
+
+fn main() {
+    let config = match ServerConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Usage: server [--port <port>] [--world <file>] [--seed <seed>] [--day-length <seconds>]");
+            std::process::exit(1);
+        }
+    };
+
+    let mut loader = ModLoader::new();
+    if let Err(e) = loader.load_mods() {
+        println!("Failed to load mods: {}", e);
+    }
+
+    match Server::new(config, &loader) {
+        Ok(mut server) => server.run(),
+        Err(e) => {
+            eprintln!("Failed to start server: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
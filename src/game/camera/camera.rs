@@ -0,0 +1,111 @@
+use macroquad::prelude::*;
+
+use crate::{input::{action::Action, input::Input}, game::utils::draw::view_rect, utils::consts::{BASE_ZOOM, CAMERA_DEADZONE, CAMERA_FOLLOW_SPEED, CHUNK_PIXELS, MAX_ZOOM, MIN_ZOOM, RENDER_DISTANCE, ZOOM_SPEED, ZOOM_STEP}};
+
+pub struct GameCamera {
+    pub camera: Camera2D,
+    pub zoom: f32,
+    /// Minimum load distance in chunks, from the settings.
+    pub render_distance: f32,
+    target_zoom: f32,
+    focus: Vec2,
+    shake_intensity: f32,
+    shake_duration: f32,
+    shake_timer: f32,
+}
+
+impl GameCamera {
+    pub fn new(focus: Vec2) -> Self {
+        let mut camera = Self {
+            camera: Camera2D::default(),
+            zoom: 1.0,
+            render_distance: RENDER_DISTANCE,
+            target_zoom: 1.0,
+            focus,
+            shake_intensity: 0.0,
+            shake_duration: 0.0,
+            shake_timer: 0.0,
+        };
+        camera.apply(vec2(0.0, 0.0));
+        camera
+    }
+
+    pub fn handle_input(&mut self, input: &Input) {
+        let (_, wheel) = mouse_wheel();
+        if wheel > 0.0 || input.is_pressed(Action::ZoomIn) {
+            self.target_zoom += ZOOM_STEP;
+        }
+        if wheel < 0.0 || input.is_pressed(Action::ZoomOut) {
+            self.target_zoom -= ZOOM_STEP;
+        }
+        self.target_zoom = self.target_zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    pub fn update(&mut self, dt: f32, target: Vec2) {
+        self.zoom += (self.target_zoom - self.zoom) * (1.0 - (-ZOOM_SPEED * dt).exp());
+
+        let offset = target - self.focus;
+        let distance = offset.length();
+        if distance > CAMERA_DEADZONE {
+            let excess = offset * ((distance - CAMERA_DEADZONE) / distance);
+            self.focus += excess * (1.0 - (-CAMERA_FOLLOW_SPEED * dt).exp());
+        }
+
+        let mut shake = vec2(0.0, 0.0);
+        if self.shake_timer > 0.0 {
+            self.shake_timer = (self.shake_timer - dt).max(0.0);
+            let strength = self.shake_intensity * self.shake_timer / self.shake_duration;
+            shake = vec2(
+                rand::gen_range(-strength, strength),
+                rand::gen_range(-strength, strength)
+            );
+        }
+
+        self.apply(shake);
+    }
+
+    /// Starts a shake that fades out over `duration` seconds. A stronger
+    /// running shake is never replaced by a weaker one.
+    pub fn shake(&mut self, intensity: f32, duration: f32) {
+        if duration <= 0.0 {
+            return;
+        }
+        let current = if self.shake_timer > 0.0 {
+            self.shake_intensity * self.shake_timer / self.shake_duration
+        } else {
+            0.0
+        };
+        if intensity >= current {
+            self.shake_intensity = intensity;
+            self.shake_duration = duration;
+            self.shake_timer = duration;
+        }
+    }
+
+    pub fn mouse_world_pos(&self) -> Vec2 {
+        self.camera.screen_to_world(Vec2::from(mouse_position()))
+    }
+
+    pub fn view_rect(&self) -> Rect {
+        view_rect(&self.camera)
+    }
+
+    /// Chunk radius that covers the whole view at the current zoom.
+    pub fn load_distance(&self) -> f32 {
+        let view = self.view_rect();
+        let half_diagonal = vec2(view.w, view.h).length() / 2.0;
+        (half_diagonal / CHUNK_PIXELS).ceil().max(self.render_distance)
+    }
+
+    fn apply(&mut self, shake: Vec2) {
+        let base_zoom = BASE_ZOOM * self.zoom;
+
+        let aspect_ratio = screen_width() / screen_height();
+        self.camera.zoom = if aspect_ratio > 1.0 {
+            vec2(base_zoom / aspect_ratio, base_zoom)
+        } else {
+            vec2(base_zoom, base_zoom * aspect_ratio)
+        };
+        self.camera.target = self.focus + shake;
+    }
+}
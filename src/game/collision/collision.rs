@@ -0,0 +1,146 @@
+use macroquad::math::{Rect, Vec2};
+
+/// How many times a move can slide along another surface after hitting one.
+const MAX_SLIDES: usize = 3;
+/// Gap kept between a box and the surface it stopped at. Solids are also
+/// shrunk by it, so boxes touching them after rounding errors still slide.
+const SKIN: f32 = 0.01;
+
+/// First contact of a moving box with a solid one.
+pub struct Hit {
+    /// Fraction of the move done before touching, from 0 to 1.
+    pub time: f32,
+    /// Points out of the surface that was hit.
+    pub normal: Vec2,
+}
+
+/// Like `Rect::overlaps`, but boxes that only touch don't count.
+pub fn intersects(a: &Rect, b: &Rect) -> bool {
+    a.left() < b.right() && a.right() > b.left() && a.top() < b.bottom() && a.bottom() > b.top()
+}
+
+/// Swept AABB test of `moving` going by `delta` against the still `solid`.
+/// Returns `None` if they don't touch during the move or already overlap.
+pub fn sweep(moving: &Rect, delta: Vec2, solid: &Rect) -> Option<Hit> {
+    let solid = &Rect::new(solid.x + SKIN, solid.y + SKIN, solid.w - SKIN * 2.0, solid.h - SKIN * 2.0);
+    if intersects(moving, solid) {
+        return None;
+    }
+
+    // Times at which the box enters and leaves the solid on each axis.
+    let axis = |delta: f32, min: f32, max: f32, solid_min: f32, solid_max: f32| -> (f32, f32) {
+        if delta > 0.0 {
+            ((solid_min - max) / delta, (solid_max - min) / delta)
+        } else if delta < 0.0 {
+            ((solid_max - min) / delta, (solid_min - max) / delta)
+        } else if max > solid_min && min < solid_max {
+            (f32::NEG_INFINITY, f32::INFINITY)
+        } else {
+            (f32::INFINITY, f32::NEG_INFINITY)
+        }
+    };
+    let (entry_x, exit_x) = axis(delta.x, moving.left(), moving.right(), solid.left(), solid.right());
+    let (entry_y, exit_y) = axis(delta.y, moving.top(), moving.bottom(), solid.top(), solid.bottom());
+
+    let entry = entry_x.max(entry_y);
+    let exit = exit_x.min(exit_y);
+    if entry > exit || !(0.0..=1.0).contains(&entry) {
+        return None;
+    }
+
+    let normal = if entry_x > entry_y {
+        Vec2::new(-delta.x.signum(), 0.0)
+    } else {
+        Vec2::new(0.0, -delta.y.signum())
+    };
+    Some(Hit { time: entry, normal })
+}
+
+/// Moves `rect` by `delta`, stopping at the first solid it runs into and
+/// sliding along it with what is left of the move. Solids the box already
+/// overlaps are ignored so nothing gets stuck. Returns how far it moved.
+pub fn move_and_slide(rect: Rect, delta: Vec2, solids: &[Rect]) -> Vec2 {
+    let mut moved = Vec2::ZERO;
+    let mut remaining = delta;
+
+    for _ in 0..MAX_SLIDES {
+        if remaining == Vec2::ZERO {
+            break;
+        }
+        let moving = rect.offset(moved);
+        let hit = solids.iter()
+            .filter_map(|solid| sweep(&moving, remaining, solid))
+            .min_by(|a, b| a.time.total_cmp(&b.time));
+
+        let Some(hit) = hit else {
+            moved += remaining;
+            break;
+        };
+        moved += remaining * hit.time + hit.normal * SKIN;
+        let rest = remaining * (1.0 - hit.time);
+        remaining = rest - hit.normal * rest.dot(hit.normal);
+    }
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::math::vec2;
+
+    use super::*;
+
+    const WALL: Rect = Rect { x: 100.0, y: 0.0, w: 32.0, h: 200.0 };
+
+    #[test]
+    fn stops_flush_against_a_wall() {
+        let player = Rect::new(50.0, 50.0, 20.0, 20.0);
+        let moved = move_and_slide(player, vec2(100.0, 0.0), &[WALL]);
+
+        let right = player.right() + moved.x;
+        assert!(right <= WALL.left(), "went into the wall: {right}");
+        assert!(WALL.left() - right < 0.1, "stopped short: {right}");
+        assert_eq!(moved.y, 0.0);
+    }
+
+    #[test]
+    fn slides_along_a_wall_on_diagonal_input() {
+        let player = Rect::new(50.0, 50.0, 20.0, 20.0);
+        let moved = move_and_slide(player, vec2(60.0, 40.0), &[WALL]);
+
+        assert!(player.right() + moved.x <= WALL.left());
+        assert!((moved.y - 40.0).abs() < 0.1, "lost the slide: {moved}");
+    }
+
+    #[test]
+    fn stops_at_a_corner() {
+        // An inside corner: the box runs into the wall and the floor at once.
+        let floor = Rect::new(0.0, 100.0, 132.0, 32.0);
+        let player = Rect::new(60.0, 60.0, 20.0, 20.0);
+        let moved = move_and_slide(player, vec2(50.0, 50.0), &[WALL, floor]);
+        let end = player.offset(moved);
+
+        assert!(!intersects(&end, &WALL) && !intersects(&end, &floor), "ended inside a solid: {end:?}");
+        assert!(WALL.left() - end.right() < 0.1 && floor.top() - end.bottom() < 0.1, "stopped short: {end:?}");
+    }
+
+    #[test]
+    fn hits_a_corner_diagonally_without_tunneling() {
+        let block = Rect::new(100.0, 100.0, 32.0, 32.0);
+        let player = Rect::new(60.0, 60.0, 20.0, 20.0);
+        let moved = move_and_slide(player, vec2(40.0, 40.0), &[block]);
+
+        assert!(!intersects(&player.offset(moved), &block));
+    }
+
+    #[test]
+    fn overlapping_box_gets_out_but_not_through_other_solids() {
+        let stuck_in = Rect::new(40.0, 40.0, 32.0, 32.0);
+        let player = Rect::new(50.0, 50.0, 20.0, 20.0);
+        let moved = move_and_slide(player, vec2(100.0, 0.0), &[stuck_in, WALL]);
+        let end = player.offset(moved);
+
+        assert!(moved.x > 0.0, "got stuck");
+        assert!(!intersects(&end, &WALL), "tunneled into the wall: {end:?}");
+        assert!(WALL.left() - end.right() < 0.1);
+    }
+}
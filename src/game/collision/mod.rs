@@ -0,0 +1 @@
+pub mod collision;
\ No newline at end of file
@@ -0,0 +1,155 @@
+use std::{error::Error, fs};
+
+use game_core::{RecipeItem, RecipeMod};
+
+use crate::game::item::{inventory::{Inventory, ItemStack}, item::Item};
+
+pub struct Recipe {
+    pub id: String,
+    pub ingredients: Vec<ItemStack>,
+    pub result: ItemStack,
+}
+
+impl TryFrom<&RecipeMod> for Recipe {
+    type Error = String;
+
+    fn try_from(recipe: &RecipeMod) -> Result<Self, Self::Error> {
+        let stack = |item: &RecipeItem| {
+            Item::from_id(&item.item)
+                .map(|id| ItemStack::new(id, item.count))
+                .ok_or_else(|| format!("unknown item {}", item.item))
+        };
+
+        Ok(Self {
+            id: recipe.id.clone(),
+            ingredients: recipe.ingredients.iter().map(stack).collect::<Result<_, _>>()?,
+            result: stack(&recipe.result)?,
+        })
+    }
+}
+
+impl Recipe {
+    pub fn can_craft(&self, inventory: &Inventory) -> bool {
+        self.ingredients.iter().all(|stack| inventory.count(&stack.item) >= stack.count)
+    }
+}
+
+/// Every known recipe: the built-in ones followed by those from mods.
+#[derive(Default)]
+pub struct RecipeBook {
+    pub recipes: Vec<Recipe>,
+}
+
+impl RecipeBook {
+    pub fn load(filename: &str, custom_recipes: &[RecipeMod]) -> Self {
+        let mut book = Self::default();
+        match Self::read(filename) {
+            Ok(recipes) => book.add(&recipes),
+            Err(e) => println!("Failed to load recipes from {}: {}", filename, e),
+        }
+        book.add(custom_recipes);
+        book
+    }
+
+    fn read(filename: &str) -> Result<Vec<RecipeMod>, Box<dyn Error>> {
+        let json = fs::read_to_string(filename)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Recipes with the id of an existing one replace it.
+    pub fn add(&mut self, recipes: &[RecipeMod]) {
+        for recipe in recipes {
+            match Recipe::try_from(recipe) {
+                Ok(recipe) => match self.recipes.iter_mut().find(|r| r.id == recipe.id) {
+                    Some(existing) => *existing = recipe,
+                    None => self.recipes.push(recipe),
+                },
+                Err(e) => println!("Skipping recipe {}: {}", recipe.id, e),
+            }
+        }
+    }
+
+    /// Takes the ingredients and adds the result. Nothing changes if the
+    /// ingredients are missing or the result does not fit.
+    pub fn craft(&self, index: usize, inventory: &mut Inventory) -> bool {
+        let Some(recipe) = self.recipes.get(index) else {
+            return false;
+        };
+        if !recipe.can_craft(inventory) {
+            return false;
+        }
+
+        let mut crafted = inventory.clone();
+        for stack in &recipe.ingredients {
+            crafted.remove(&stack.item, stack.count);
+        }
+        if crafted.add(recipe.result.item.clone(), recipe.result.count) > 0 {
+            return false;
+        }
+        *inventory = crafted;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::entity::block::block::BlockType;
+
+    use super::*;
+
+    fn item(id: &str, count: u32) -> RecipeItem {
+        RecipeItem { item: id.to_owned(), count }
+    }
+
+    /// Three wood and a cactus piece make two torches.
+    fn torches() -> RecipeBook {
+        let mut book = RecipeBook::default();
+        book.add(&[RecipeMod {
+            id: "torch".to_owned(),
+            ingredients: vec![item("wood", 3), item("cactus_piece", 1)],
+            result: item("block:torch", 2),
+        }]);
+        book
+    }
+
+    #[test]
+    fn refuses_when_ingredients_are_short() {
+        let book = torches();
+        let mut inventory = Inventory::default();
+        inventory.add(Item::Wood, 2);
+        inventory.add(Item::CactusPiece, 1);
+
+        assert!(!book.craft(0, &mut inventory));
+        assert_eq!(inventory.count(&Item::Wood), 2);
+        assert_eq!(inventory.count(&Item::CactusPiece), 1);
+        assert_eq!(inventory.count(&Item::Block(BlockType::Torch)), 0);
+    }
+
+    #[test]
+    fn consumes_exactly_the_recipe_amounts() {
+        let book = torches();
+        let mut inventory = Inventory::default();
+        inventory.add(Item::Wood, 5);
+        inventory.add(Item::CactusPiece, 2);
+
+        assert!(book.craft(0, &mut inventory));
+        assert_eq!(inventory.count(&Item::Wood), 2);
+        assert_eq!(inventory.count(&Item::CactusPiece), 1);
+        assert_eq!(inventory.count(&Item::Block(BlockType::Torch)), 2);
+    }
+
+    #[test]
+    fn refuses_when_the_result_does_not_fit() {
+        let book = torches();
+        let mut inventory = Inventory::default();
+        for slot in inventory.slots.iter_mut() {
+            *slot = Some(ItemStack::new(Item::Hoe, 1));
+        }
+        inventory.slots[0] = Some(ItemStack::new(Item::Wood, 4));
+        inventory.slots[1] = Some(ItemStack::new(Item::CactusPiece, 2));
+
+        assert!(!book.craft(0, &mut inventory));
+        assert_eq!(inventory.count(&Item::Wood), 4);
+        assert_eq!(inventory.count(&Item::CactusPiece), 2);
+    }
+}
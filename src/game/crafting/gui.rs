@@ -0,0 +1,73 @@
+use macroquad::prelude::*;
+
+use crate::game::{entity::{block::textures::BlockTextures, player::gui::PlayerGui}, item::inventory::Inventory, world::tile::textures::TileTextures};
+
+use super::crafting::RecipeBook;
+
+const ROW_HEIGHT: f32 = 32.0;
+const PANEL_WIDTH: f32 = 360.0;
+
+/// List of recipes opened with the crafting action. Clicking a row crafts it.
+#[derive(Default)]
+pub struct CraftingGui {
+    pub open: bool,
+}
+
+impl CraftingGui {
+    fn row_rect(row: usize, rows: usize) -> Rect {
+        let height = rows as f32 * ROW_HEIGHT;
+        Rect::new(
+            (screen_width() - PANEL_WIDTH) / 2.0,
+            (screen_height() - height) / 2.0 + row as f32 * ROW_HEIGHT,
+            PANEL_WIDTH,
+            ROW_HEIGHT,
+        )
+    }
+
+    /// Recipe under a point in screen coordinates.
+    pub fn recipe_at(&self, point: Vec2, book: &RecipeBook) -> Option<usize> {
+        if !self.open {
+            return None;
+        }
+        (0..book.recipes.len()).find(|row| Self::row_rect(*row, book.recipes.len()).contains(point))
+    }
+
+    pub fn draw(&self, book: &RecipeBook, inventory: &Inventory, tiles: &TileTextures, blocks: &BlockTextures) {
+        if !self.open {
+            return;
+        }
+        let rows = book.recipes.len();
+        let title_rect = Self::row_rect(0, rows);
+        draw_text("CRAFTING", title_rect.x, title_rect.y - 10.0, 24.0, WHITE);
+        if rows == 0 {
+            draw_text("No recipes", title_rect.x, title_rect.y + 20.0, 20.0, GRAY);
+            return;
+        }
+
+        let mouse = Vec2::from(mouse_position());
+        for (row, recipe) in book.recipes.iter().enumerate() {
+            let rect = Self::row_rect(row, rows);
+            let alpha = if rect.contains(mouse) { 0.8 } else { 0.6 };
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.0, 0.0, 0.0, alpha));
+
+            if let Some(icon) = PlayerGui::item_icon(&recipe.result.item, tiles, blocks) {
+                icon.draw(vec2(rect.x + 4.0, rect.y + 4.0), vec2(ROW_HEIGHT - 8.0, ROW_HEIGHT - 8.0), 0.0);
+            }
+
+            let color = if recipe.can_craft(inventory) { WHITE } else { GRAY };
+            let result = if recipe.result.count > 1 {
+                format!("{} x{}", recipe.result.item.name(), recipe.result.count)
+            } else {
+                recipe.result.item.name()
+            };
+            draw_text(&result, rect.x + ROW_HEIGHT + 4.0, rect.y + 21.0, 20.0, color);
+
+            let ingredients = recipe.ingredients.iter()
+                .map(|stack| format!("{} {}", stack.count, stack.item.name()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let width = measure_text(&ingredients, None, 16, 1.0).width;
+            draw_text(&ingredients, rect.x + rect.w - width - 6.0, rect.y + 20.0, 16.0, color);
+        }
+    }
+}
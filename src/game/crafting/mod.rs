@@ -0,0 +1,2 @@
+pub mod crafting;
+pub mod gui;
\ No newline at end of file
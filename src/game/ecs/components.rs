@@ -0,0 +1,224 @@
+use macroquad::math::{Rect, Vec2};
+
+use crate::{game::{entity::player::direction::Direction, item::inventory::ItemStack, world::generator::biome::Biome}, utils::consts::{CROP_STAGES, CROP_STAGE_SECONDS}};
+
+/// Top left corner and size of an entity.
+#[derive(Clone, Copy)]
+pub struct Transform {
+    pub pos: Vec2,
+    pub size: Vec2,
+}
+
+impl Transform {
+    pub fn new(pos: Vec2, size: Vec2) -> Self {
+        Self { pos, size }
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.pos.x, self.pos.y, self.size.x, self.size.y)
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.pos + self.size / 2.0
+    }
+
+    /// Y coordinate entities are sorted by when drawn.
+    pub fn depth(&self) -> f32 {
+        self.pos.y + self.size.y
+    }
+}
+
+pub struct Velocity(pub Vec2);
+
+/// Position between the last two simulation steps, used for drawing.
+pub struct Interpolation {
+    pub prev_pos: Vec2,
+    pub render_pos: Vec2,
+}
+
+impl Interpolation {
+    pub fn new(pos: Vec2) -> Self {
+        Self { prev_pos: pos, render_pos: pos }
+    }
+}
+
+/// Area other entities can't walk into, relative to the transform.
+pub struct Collider {
+    pub offset: Vec2,
+    pub size: Vec2,
+}
+
+impl Collider {
+    pub fn new(offset: Vec2, size: Vec2) -> Self {
+        Self { offset, size }
+    }
+
+    pub fn rect(&self, transform: &Transform) -> Rect {
+        let pos = transform.pos + self.offset;
+        Rect::new(pos.x, pos.y, self.size.x, self.size.y)
+    }
+}
+
+/// How an entity is drawn.
+pub enum Sprite {
+    Tree(Biome),
+    Cactus,
+    Crop,
+    Torch,
+    Campfire,
+    /// Id of the mob type.
+    Mob(String),
+    Player,
+}
+
+pub struct Animation {
+    pub frame: u32,
+    pub timer: f32,
+    pub moving: bool,
+    pub facing: Direction,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            frame: 0,
+            timer: 0.0,
+            moving: false,
+            facing: Direction::Down,
+        }
+    }
+}
+
+impl Animation {
+    /// Advances the walking frames every `frame_time` while moving.
+    /// Returns `true` when a new frame starts, which is a footstep.
+    pub fn update(&mut self, dt: f32, moving: bool, frame_time: f32) -> bool {
+        self.moving = moving;
+        if moving {
+            self.timer += dt;
+            if self.timer > frame_time {
+                self.timer = 0.0;
+                self.frame += 1;
+                return true;
+            }
+        } else {
+            self.timer = 0.0;
+            self.frame = 0;
+        }
+        false
+    }
+
+    /// Faces the main direction of `velocity`.
+    pub fn face(&mut self, velocity: Vec2) {
+        if velocity == Vec2::ZERO {
+            return;
+        }
+        self.facing = if velocity.x.abs() > velocity.y.abs() {
+            if velocity.x > 0.0 { Direction::Right } else { Direction::Left }
+        } else if velocity.y > 0.0 {
+            Direction::Down
+        } else {
+            Direction::Up
+        };
+    }
+}
+
+pub struct Health {
+    pub hp: i32,
+    pub max_hp: i32,
+    /// Counts down after taking damage, entities are drawn tinted meanwhile.
+    pub hurt_timer: f32,
+}
+
+impl Health {
+    pub fn new(max_hp: i32) -> Self {
+        Self { hp: max_hp, max_hp, hurt_timer: 0.0 }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.hp <= 0
+    }
+
+    pub fn is_hurt(&self) -> bool {
+        self.hurt_timer > 0.0
+    }
+
+    /// Returns `true` once the entity is dead.
+    pub fn damage(&mut self, amount: i32, hurt_time: f32) -> bool {
+        self.hp = (self.hp - amount).max(0);
+        self.hurt_timer = hurt_time;
+        self.is_dead()
+    }
+
+    pub fn heal(&mut self, amount: i32) {
+        self.hp = (self.hp + amount).min(self.max_hp);
+    }
+}
+
+/// Tile centers an entity is walking along.
+pub struct Path {
+    /// Remaining waypoints, the next one last.
+    waypoints: Vec<Vec2>,
+    /// Seconds since the path was found.
+    pub age: f32,
+}
+
+impl Path {
+    /// `waypoints` go from the start to the goal.
+    pub fn new(mut waypoints: Vec<Vec2>) -> Self {
+        waypoints.reverse();
+        Self { waypoints, age: 0.0 }
+    }
+
+    /// Direction from `pos` to the next waypoint, of length 1. Waypoints
+    /// within `reach` count as reached. Returns `None` at the end of the path.
+    pub fn direction(&mut self, pos: Vec2, reach: f32) -> Option<Vec2> {
+        while let Some(next) = self.waypoints.last() {
+            if next.distance(pos) > reach {
+                return Some((*next - pos).normalize_or_zero());
+            }
+            self.waypoints.pop();
+        }
+        None
+    }
+
+    /// Remaining waypoints, the next one first.
+    pub fn waypoints(&self) -> impl Iterator<Item = &Vec2> {
+        self.waypoints.iter().rev()
+    }
+}
+
+/// Light emitted by a block, up to `MAX_LIGHT`.
+pub struct LightSource(pub u8);
+
+/// Damage dealt to players touching the entity.
+pub struct ContactDamage(pub i32);
+
+/// Grows through `CROP_STAGES` stages, faster next to water.
+pub struct Growth {
+    /// Seconds of growth so far.
+    pub seconds: f32,
+    /// Replace the usual drops once fully grown.
+    pub ripe_drops: Vec<ItemStack>,
+}
+
+impl Growth {
+    pub fn stage(&self) -> u32 {
+        ((self.seconds / CROP_STAGE_SECONDS) as u32).min(CROP_STAGES - 1)
+    }
+
+    pub fn is_ripe(&self) -> bool {
+        self.stage() == CROP_STAGES - 1
+    }
+
+    /// Returns `true` if the growth stage changed.
+    pub fn grow(&mut self, seconds: f32) -> bool {
+        if self.is_ripe() {
+            return false;
+        }
+        let stage = self.stage();
+        self.seconds += seconds;
+        self.stage() != stage
+    }
+}
+
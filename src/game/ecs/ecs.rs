@@ -0,0 +1,162 @@
+use crate::game::{entity::{block::block::BlockType, mob::{ai::Ai, mob::Mob}, player::player::Player}, item::inventory::ItemStack};
+
+use super::components::{Animation, Collider, ContactDamage, Growth, Health, Interpolation, LightSource, Path, Sprite, Transform, Velocity};
+
+/// Handle to an entity. Stays invalid after the entity is despawned, even
+/// when its slot is reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    /// The entity as one number, to name it over the network.
+    pub fn to_bits(self) -> u64 {
+        (self.generation as u64) << 32 | self.index as u64
+    }
+}
+
+/// Components of one type, indexed by entity.
+pub struct Storage<T> {
+    items: Vec<Option<(u32, T)>>,
+}
+
+impl<T> Default for Storage<T> {
+    fn default() -> Self {
+        Self { items: Vec::new() }
+    }
+}
+
+impl<T> Storage<T> {
+    pub fn insert(&mut self, entity: Entity, component: T) {
+        let index = entity.index as usize;
+        if index >= self.items.len() {
+            self.items.resize_with(index + 1, || None);
+        }
+        self.items[index] = Some((entity.generation, component));
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let slot = self.items.get_mut(entity.index as usize)?;
+        match slot {
+            Some((generation, _)) if *generation == entity.generation => slot.take().map(|(_, component)| component),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        match self.items.get(entity.index as usize)? {
+            Some((generation, component)) if *generation == entity.generation => Some(component),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        match self.items.get_mut(entity.index as usize)? {
+            Some((generation, component)) if *generation == entity.generation => Some(component),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.get(entity).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.items.iter().enumerate().filter_map(|(index, slot)| {
+            slot.as_ref().map(|(generation, component)| (Entity { index: index as u32, generation: *generation }, component))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.items.iter_mut().enumerate().filter_map(|(index, slot)| {
+            slot.as_mut().map(|(generation, component)| (Entity { index: index as u32, generation: *generation }, component))
+        })
+    }
+}
+
+/// Every block, mob and player in the world, stored as components. What an
+/// entity does comes from the components it has, systems run over all
+/// entities with the components they need.
+#[derive(Default)]
+pub struct Ecs {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    pub transforms: Storage<Transform>,
+    pub velocities: Storage<Velocity>,
+    pub interpolations: Storage<Interpolation>,
+    pub colliders: Storage<Collider>,
+    pub sprites: Storage<Sprite>,
+    pub animations: Storage<Animation>,
+    pub healths: Storage<Health>,
+    pub contact_damages: Storage<ContactDamage>,
+    pub growths: Storage<Growth>,
+    pub lights: Storage<LightSource>,
+    pub paths: Storage<Path>,
+    /// Items given to whoever destroys the entity.
+    pub drops: Storage<Vec<ItemStack>>,
+    /// Marks blocks, which are saved with their chunk.
+    pub blocks: Storage<BlockType>,
+    pub mobs: Storage<Mob>,
+    pub ais: Storage<Ai>,
+    pub players: Storage<Player>,
+}
+
+impl Ecs {
+    pub fn spawn(&mut self) -> Entity {
+        match self.free.pop() {
+            Some(index) => {
+                self.alive[index as usize] = true;
+                Entity { index, generation: self.generations[index as usize] }
+            }
+            None => {
+                self.generations.push(0);
+                self.alive.push(true);
+                Entity { index: self.generations.len() as u32 - 1, generation: 0 }
+            }
+        }
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        let index = entity.index as usize;
+        self.alive.get(index) == Some(&true) && self.generations[index] == entity.generation
+    }
+
+    /// Removes the entity with all of its components.
+    pub fn despawn(&mut self, entity: Entity) {
+        if !self.is_alive(entity) {
+            return;
+        }
+        self.transforms.remove(entity);
+        self.velocities.remove(entity);
+        self.interpolations.remove(entity);
+        self.colliders.remove(entity);
+        self.sprites.remove(entity);
+        self.animations.remove(entity);
+        self.healths.remove(entity);
+        self.contact_damages.remove(entity);
+        self.growths.remove(entity);
+        self.lights.remove(entity);
+        self.paths.remove(entity);
+        self.drops.remove(entity);
+        self.blocks.remove(entity);
+        self.mobs.remove(entity);
+        self.ais.remove(entity);
+        self.players.remove(entity);
+
+        let index = entity.index as usize;
+        self.alive[index] = false;
+        self.generations[index] += 1;
+        self.free.push(entity.index);
+    }
+
+    pub fn len(&self) -> usize {
+        self.alive.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
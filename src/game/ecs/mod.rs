@@ -0,0 +1,3 @@
+pub mod components;
+pub mod ecs;
+pub mod systems;
\ No newline at end of file
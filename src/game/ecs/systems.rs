@@ -0,0 +1,43 @@
+use macroquad::math::Rect;
+
+use crate::game::item::inventory::ItemStack;
+
+use super::ecs::{Ecs, Entity};
+
+/// How long blocks and mobs are drawn tinted after being hit.
+pub const HURT_TIME: f32 = 0.3;
+
+pub fn update_hurt_timers(ecs: &mut Ecs, dt: f32) {
+    for (_, health) in ecs.healths.iter_mut() {
+        health.hurt_timer = (health.hurt_timer - dt).max(0.0);
+    }
+}
+
+/// Blends the last two simulation steps; `alpha` is how far the renderer
+/// is into the next step.
+pub fn interpolate(ecs: &mut Ecs, alpha: f32) {
+    for (entity, interpolation) in ecs.interpolations.iter_mut() {
+        if let Some(transform) = ecs.transforms.get(entity) {
+            interpolation.render_pos = interpolation.prev_pos.lerp(transform.pos, alpha);
+        }
+    }
+}
+
+/// Items an entity leaves behind, the ripe drops once it is fully grown.
+pub fn drops(ecs: &Ecs, entity: Entity) -> Vec<ItemStack> {
+    match ecs.growths.get(entity) {
+        Some(growth) if growth.is_ripe() => growth.ripe_drops.clone(),
+        _ => ecs.drops.get(entity).cloned().unwrap_or_default(),
+    }
+}
+
+/// Highest contact damage of `entities` overlapping `rect`.
+pub fn contact_damage(ecs: &Ecs, entities: impl Iterator<Item = Entity>, rect: Rect) -> i32 {
+    entities
+        .filter_map(|entity| {
+            let damage = ecs.contact_damages.get(entity)?;
+            ecs.transforms.get(entity)?.rect().overlaps(&rect).then_some(damage.0)
+        })
+        .max()
+        .unwrap_or(0)
+}
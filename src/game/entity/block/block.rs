@@ -1,48 +1,72 @@
 use macroquad::prelude::*;
 
-use std::collections::HashMap;
-
 use serde::{Deserialize, Serialize};
 
-use crate::save::block::BlockSave;
+use crate::game::{ecs::{components::{Sprite, Transform}, ecs::{Ecs, Entity}}, world::generator::biome::Biome};
+
+use super::{blocks::{cactus, campfire, crop, torch, tree}, textures::BlockTextures};
 
+/// Kind of a block entity. Each kind has a module in `blocks` that spawns
+/// it with its components and draws it.
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, Eq, PartialEq)]
 pub enum BlockType {
     Cactus,
     Tree,
+    Crop,
+    Torch,
+    Campfire,
 }
 
-pub struct BlockCache {
-    trees: Vec<Box<dyn Block>>,
-    cacti: Vec<Box<dyn Block>>,
-    textures: HashMap<BlockType, Texture2D>,
-    max_pool_size: usize,
-}
+impl BlockType {
+    /// Id used by recipes and particle emitters, e.g. `tree`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            BlockType::Cactus => "cactus",
+            BlockType::Tree => "tree",
+            BlockType::Crop => "crop",
+            BlockType::Torch => "torch",
+            BlockType::Campfire => "campfire",
+        }
+    }
 
-impl BlockCache {
-    pub fn new(max_size: usize) -> Self {
-        Self {
-            trees: Vec::with_capacity(max_size),
-            cacti: Vec::with_capacity(max_size),
-            textures: HashMap::new(),
-            max_pool_size: max_size,
+    pub fn size(&self) -> Vec2 {
+        match self {
+            BlockType::Cactus => cactus::SIZE,
+            BlockType::Tree => tree::SIZE,
+            BlockType::Crop => crop::SIZE,
+            BlockType::Torch => torch::SIZE,
+            BlockType::Campfire => campfire::SIZE,
         }
     }
 
-    pub fn get_block(&mut self, block_type: BlockType) -> Option<Box<dyn Block>> {
-        match block_type {
-            BlockType::Tree => self.trees.pop(),
-            BlockType::Cactus => self.cacti.pop(),
+    /// `biome` is the biome of the tile the block stands on.
+    pub fn spawn(&self, ecs: &mut Ecs, pos: Vec2, biome: Biome) -> Entity {
+        match self {
+            BlockType::Cactus => cactus::spawn(ecs, pos),
+            BlockType::Tree => tree::spawn(ecs, pos, biome),
+            BlockType::Crop => crop::spawn(ecs, pos),
+            BlockType::Torch => torch::spawn(ecs, pos),
+            BlockType::Campfire => campfire::spawn(ecs, pos),
         }
     }
 }
 
-pub trait Block {
-    fn get_position(&self) -> Vec2;
-    fn get_size(&self) -> Vec2;
-    fn draw(&self);
-    fn set_texture(&mut self, texture: Texture2D);
-    fn set_position(&mut self, pos: Vec2);
-    fn to_save(&self) -> BlockSave;
-    fn from_save(save: BlockSave) -> Self where Self: Sized;
+/// Draws block sprites. Returns `false` for sprites that aren't blocks.
+pub fn draw_block(ecs: &Ecs, entity: Entity, transform: &Transform, sprite: &Sprite, textures: &BlockTextures, time: f32) -> bool {
+    match sprite {
+        Sprite::Tree(_) => tree::draw(transform, textures, time),
+        Sprite::Cactus => cactus::draw(transform, textures, time),
+        Sprite::Crop => crop::draw(transform, ecs.growths.get(entity)),
+        Sprite::Torch => torch::draw(transform),
+        Sprite::Campfire => campfire::draw(transform),
+        _ => return false,
+    }
+    true
+}
+
+/// Part of a block drawn above every other block and entity.
+pub fn draw_canopy(transform: &Transform, sprite: &Sprite, textures: &BlockTextures, time: f32) {
+    if let Sprite::Tree(biome) = sprite {
+        tree::draw_canopy(transform, biome, textures, time);
+    }
 }
@@ -1,66 +1,22 @@
 use macroquad::prelude::*;
 
-use crate::{game::entity::block::block::{Block, BlockType}, save::{block::BlockSave, vec2::Vec2Save}};
-
-pub struct Cactus {
-    pub pos: Vec2,
-    pub size: Vec2,
-    texture: Option<Texture2D>,
+use crate::{game::{ecs::{components::{Collider, ContactDamage, Health, Sprite, Transform}, ecs::{Ecs, Entity}}, entity::block::{block::BlockType, textures::BlockTextures}, item::{inventory::ItemStack, item::Item}}, utils::consts::CACTUS_DAMAGE};
+
+const CACTUS_HP: i32 = 3;
+pub const SIZE: Vec2 = vec2(32.0, 32.0);
+
+pub fn spawn(ecs: &mut Ecs, pos: Vec2) -> Entity {
+    let entity = ecs.spawn();
+    ecs.transforms.insert(entity, Transform::new(pos, SIZE));
+    ecs.colliders.insert(entity, Collider::new(vec2(4.0, 4.0), vec2(24.0, 28.0)));
+    ecs.sprites.insert(entity, Sprite::Cactus);
+    ecs.healths.insert(entity, Health::new(CACTUS_HP));
+    ecs.contact_damages.insert(entity, ContactDamage(CACTUS_DAMAGE));
+    ecs.drops.insert(entity, vec![ItemStack::new(Item::CactusPiece, 2)]);
+    ecs.blocks.insert(entity, BlockType::Cactus);
+    entity
 }
 
-impl Block for Cactus {
-    fn get_position(&self) -> Vec2 {
-        self.pos
-    }
-
-    fn get_size(&self) -> Vec2 {
-        self.size
-    }
-
-    fn draw(&self) {
-        if let Some(tex) = &self.texture {
-            draw_texture_ex(
-                tex,
-                self.pos.x,
-                self.pos.y,
-                WHITE,
-                DrawTextureParams {
-                    dest_size: Some(self.size),
-                    ..Default::default()
-                }
-            );
-        }
-    }
-
-    fn set_texture(&mut self, texture: Texture2D) {
-        self.texture = Some(texture);
-    }
-    fn to_save(&self) -> BlockSave {
-        BlockSave {
-            pos: Vec2Save::from(self.pos),
-            block_type: BlockType::Cactus,
-        }
-    }
-
-    fn from_save(save: BlockSave) -> Self {
-        Self {
-            pos: Vec2::from(save.pos),
-            size: vec2(32.0,32.0),
-            texture: None,
-        }
-    }
-    
-    fn set_position(&mut self, pos: Vec2) {
-        self.pos = pos;
-    }
+pub fn draw(transform: &Transform, textures: &BlockTextures, time: f32) {
+    textures.cactus.draw(transform.pos, transform.size, time);
 }
-
-impl Cactus {
-    pub fn new(pos: Vec2) -> Self {
-        Self {
-            pos,
-            size: vec2(32.0, 32.0),
-            texture: None,
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,27 @@
+use macroquad::prelude::*;
+
+use crate::{game::{ecs::{components::{Collider, Health, LightSource, Sprite, Transform}, ecs::{Ecs, Entity}}, entity::block::block::BlockType, item::{inventory::ItemStack, item::Item}}, utils::consts::CAMPFIRE_LIGHT};
+
+const CAMPFIRE_HP: i32 = 2;
+pub const SIZE: Vec2 = vec2(32.0, 32.0);
+
+/// Brightest light source. Players can't walk through the fire.
+pub fn spawn(ecs: &mut Ecs, pos: Vec2) -> Entity {
+    let entity = ecs.spawn();
+    ecs.transforms.insert(entity, Transform::new(pos, SIZE));
+    ecs.colliders.insert(entity, Collider::new(vec2(4.0, 16.0), vec2(24.0, 14.0)));
+    ecs.sprites.insert(entity, Sprite::Campfire);
+    ecs.healths.insert(entity, Health::new(CAMPFIRE_HP));
+    ecs.lights.insert(entity, LightSource(CAMPFIRE_LIGHT));
+    ecs.drops.insert(entity, vec![ItemStack::new(Item::Wood, 2)]);
+    ecs.blocks.insert(entity, BlockType::Campfire);
+    entity
+}
+
+pub fn draw(transform: &Transform) {
+    let pos = transform.pos;
+    draw_rectangle(pos.x + 4.0, pos.y + 24.0, 24.0, 5.0, DARKBROWN);
+    draw_rectangle(pos.x + 8.0, pos.y + 20.0, 16.0, 5.0, BROWN);
+    draw_triangle(vec2(pos.x + 8.0, pos.y + 22.0), vec2(pos.x + 24.0, pos.y + 22.0), vec2(pos.x + 16.0, pos.y + 6.0), ORANGE);
+    draw_triangle(vec2(pos.x + 12.0, pos.y + 22.0), vec2(pos.x + 20.0, pos.y + 22.0), vec2(pos.x + 16.0, pos.y + 12.0), YELLOW);
+}
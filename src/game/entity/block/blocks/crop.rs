@@ -0,0 +1,32 @@
+use macroquad::prelude::*;
+
+use crate::game::{ecs::{components::{Growth, Health, Sprite, Transform}, ecs::{Ecs, Entity}}, entity::block::block::BlockType, item::{inventory::ItemStack, item::Item}};
+
+pub const SIZE: Vec2 = vec2(32.0, 32.0);
+
+/// Planted from seeds on tilled soil. Has no collider, so players walk
+/// through it, and gives wheat once fully grown.
+pub fn spawn(ecs: &mut Ecs, pos: Vec2) -> Entity {
+    let entity = ecs.spawn();
+    ecs.transforms.insert(entity, Transform::new(pos, SIZE));
+    ecs.sprites.insert(entity, Sprite::Crop);
+    ecs.healths.insert(entity, Health::new(1));
+    ecs.growths.insert(entity, Growth {
+        seconds: 0.0,
+        ripe_drops: vec![ItemStack::new(Item::Wheat, 2), ItemStack::new(Item::Seeds, 2)],
+    });
+    ecs.drops.insert(entity, vec![ItemStack::new(Item::Seeds, 1)]);
+    ecs.blocks.insert(entity, BlockType::Crop);
+    entity
+}
+
+pub fn draw(transform: &Transform, growth: Option<&Growth>) {
+    let stage = growth.map(|growth| growth.stage()).unwrap_or(0) as f32 + 1.0;
+    let height = transform.size.y * 0.2 * stage;
+    let color = if growth.is_some_and(|growth| growth.is_ripe()) { GOLD } else { GREEN };
+
+    for i in 0..3 {
+        let x = transform.pos.x + 6.0 + i as f32 * 9.0;
+        draw_rectangle(x, transform.pos.y + transform.size.y - 4.0 - height, 3.0, height, color);
+    }
+}
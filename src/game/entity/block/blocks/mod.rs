@@ -1,2 +1,5 @@
 pub mod cactus;
-pub mod tree;
\ No newline at end of file
+pub mod campfire;
+pub mod crop;
+pub mod torch;
+pub mod tree;
\ No newline at end of file
@@ -0,0 +1,24 @@
+use macroquad::prelude::*;
+
+use crate::{game::{ecs::{components::{Health, LightSource, Sprite, Transform}, ecs::{Ecs, Entity}}, entity::block::block::BlockType, item::{inventory::ItemStack, item::Item}}, utils::consts::TORCH_LIGHT};
+
+pub const SIZE: Vec2 = vec2(32.0, 32.0);
+
+/// Small light source. Has no collider and breaks with one hit.
+pub fn spawn(ecs: &mut Ecs, pos: Vec2) -> Entity {
+    let entity = ecs.spawn();
+    ecs.transforms.insert(entity, Transform::new(pos, SIZE));
+    ecs.sprites.insert(entity, Sprite::Torch);
+    ecs.healths.insert(entity, Health::new(1));
+    ecs.lights.insert(entity, LightSource(TORCH_LIGHT));
+    ecs.drops.insert(entity, vec![ItemStack::new(Item::Block(BlockType::Torch), 1)]);
+    ecs.blocks.insert(entity, BlockType::Torch);
+    entity
+}
+
+pub fn draw(transform: &Transform) {
+    let center = transform.center();
+    draw_rectangle(center.x - 2.0, center.y - 4.0, 4.0, 14.0, BROWN);
+    draw_circle(center.x, center.y - 6.0, 4.0, ORANGE);
+    draw_circle(center.x, center.y - 6.0, 2.0, YELLOW);
+}
@@ -1,19 +1,21 @@
-use macroquad::texture::{load_texture, Texture2D};
+use crate::{game::utils::animation::SpriteSheet, utils::consts::{TREE_FRAMES, TREE_FRAME_TIME}};
 
+/// Every block sprite is a sheet, so any of them can be animated.
 pub struct BlockTextures {
-    pub tree: Texture2D,
-    pub tree_top: Texture2D,
-    pub tree_snow_top: Texture2D,
-    pub cactus: Texture2D,
+    pub tree: SpriteSheet,
+    /// Canopies sway in the wind.
+    pub tree_top: SpriteSheet,
+    pub tree_snow_top: SpriteSheet,
+    pub cactus: SpriteSheet,
 }
     
 impl BlockTextures {
     pub async fn load() -> Self {
         Self {
-            tree: load_texture("assets/textures/blocks/tree.png").await.unwrap(),
-            tree_top: load_texture("assets/textures/blocks/tree_top.png").await.unwrap(),
-            tree_snow_top: load_texture("assets/textures/blocks/tree_snow_top.png").await.unwrap(),
-            cactus: load_texture("assets/textures/blocks/cactus.png").await.unwrap(),
+            tree: SpriteSheet::load("assets/textures/blocks/tree.png", 1, 1.0).await.unwrap(),
+            tree_top: SpriteSheet::load("assets/textures/blocks/tree_top.png", TREE_FRAMES, TREE_FRAME_TIME).await.unwrap(),
+            tree_snow_top: SpriteSheet::load("assets/textures/blocks/tree_snow_top.png", TREE_FRAMES, TREE_FRAME_TIME).await.unwrap(),
+            cactus: SpriteSheet::load("assets/textures/blocks/cactus.png", 1, 1.0).await.unwrap(),
         }
     }
-}
\ No newline at end of file
+}
\ No newline at end of file
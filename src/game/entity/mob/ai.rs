@@ -0,0 +1,73 @@
+use macroquad::{math::{vec2, Vec2}, rand::gen_range};
+
+pub enum AiState {
+    Idle,
+    /// Walks in the given direction.
+    Wander(Vec2),
+    /// Runs away from the closest player after being hit.
+    Flee,
+    /// Walks towards the closest player.
+    Chase,
+}
+
+/// Decides where a mob wants to go. Passive mobs idle and wander around,
+/// hostile ones chase players that come close.
+pub struct Ai {
+    pub state: AiState,
+    timer: f32,
+}
+
+impl Default for Ai {
+    fn default() -> Self {
+        Self {
+            state: AiState::Idle,
+            timer: gen_range(0.5, 3.0),
+        }
+    }
+}
+
+impl Ai {
+    /// Returns the direction to move in, of length 1 or zero to stand still.
+    /// `target` is the closest player in range.
+    pub fn think(&mut self, dt: f32, pos: Vec2, hostile: bool, target: Option<Vec2>) -> Vec2 {
+        self.timer -= dt;
+
+        if let (true, Some(target)) = (hostile, target) {
+            self.state = AiState::Chase;
+            return (target - pos).normalize_or_zero();
+        }
+        if let AiState::Chase = self.state {
+            self.state = AiState::Idle;
+        }
+
+        if self.timer <= 0.0 {
+            self.state = match self.state {
+                AiState::Idle => {
+                    let angle = gen_range(0.0, std::f32::consts::TAU);
+                    AiState::Wander(vec2(angle.cos(), angle.sin()))
+                }
+                _ => AiState::Idle,
+            };
+            self.timer = gen_range(1.0, 4.0);
+        }
+
+        match self.state {
+            AiState::Idle | AiState::Chase => Vec2::ZERO,
+            AiState::Wander(direction) => direction,
+            AiState::Flee => target.map(|target| (pos - target).normalize_or_zero()).unwrap_or(Vec2::ZERO),
+        }
+    }
+
+    /// Makes a passive mob run away for a while.
+    pub fn scare(&mut self) {
+        self.state = AiState::Flee;
+        self.timer = 2.0;
+    }
+
+    /// Turns around after walking into something.
+    pub fn blocked(&mut self) {
+        if let AiState::Wander(direction) = self.state {
+            self.state = AiState::Wander(-direction);
+        }
+    }
+}
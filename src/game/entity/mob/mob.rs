@@ -0,0 +1,145 @@
+use macroquad::prelude::*;
+
+use crate::{game::{collision::collision, ecs::{components::{Animation, ContactDamage, Health, Path, Sprite, Transform, Velocity}, ecs::{Ecs, Entity}}, entity::player::direction::Direction, pathfinding::pathfinding, world::{tile::state::TileState, world::World}}, utils::consts::PATH_REFRESH};
+
+use super::{ai::{Ai, AiState}, registry::MobType, textures::MobTextures};
+
+/// Marks a creature moving around by itself. Mobs are stored in the chunk
+/// they stand in.
+pub struct Mob {
+    /// Id of its `MobType`.
+    pub kind: String,
+}
+
+pub fn spawn(ecs: &mut Ecs, mob_type: &MobType, pos: Vec2) -> Entity {
+    let config = &mob_type.config;
+    let entity = ecs.spawn();
+    ecs.transforms.insert(entity, Transform::new(pos, vec2(config.size, config.size)));
+    ecs.velocities.insert(entity, Velocity(Vec2::ZERO));
+    ecs.sprites.insert(entity, Sprite::Mob(config.id.clone()));
+    ecs.animations.insert(entity, Animation::default());
+    ecs.healths.insert(entity, Health::new(config.hp));
+    if config.hostile && config.damage > 0 {
+        ecs.contact_damages.insert(entity, ContactDamage(config.damage));
+    }
+    ecs.drops.insert(entity, mob_type.drops.clone());
+    ecs.mobs.insert(entity, Mob { kind: config.id.clone() });
+    ecs.ais.insert(entity, Ai::default());
+    entity
+}
+
+/// `target` is the closest player within chasing distance.
+pub fn update(world: &mut World, entity: Entity, dt: f32, hostile: bool, speed: f32, target: Option<Vec2>) {
+    let Some(transform) = world.ecs.transforms.get(entity).copied() else {
+        return;
+    };
+    let Some(ai) = world.ecs.ais.get_mut(entity) else {
+        return;
+    };
+    let mut direction = ai.think(dt, transform.center(), hostile, target);
+    let chasing = matches!(ai.state, AiState::Chase);
+    match target.filter(|_| chasing) {
+        Some(target) => {
+            if let Some(path_direction) = chase(world, entity, transform.center(), target, dt, speed * dt) {
+                direction = path_direction;
+            }
+        }
+        None => {
+            world.ecs.paths.remove(entity);
+        }
+    }
+    let delta = direction * speed * dt;
+    let step = collision::move_and_slide(transform.rect(), delta, &solids_near(world, transform.rect()));
+    let pos = transform.pos + step;
+
+    if step.distance_squared(delta) > 0.0001 {
+        if let Some(ai) = world.ecs.ais.get_mut(entity) {
+            ai.blocked();
+        }
+    }
+    if let Some(transform) = world.ecs.transforms.get_mut(entity) {
+        transform.pos = pos;
+    }
+    if let Some(velocity) = world.ecs.velocities.get_mut(entity) {
+        velocity.0 = direction * speed;
+    }
+    let stepped = world.ecs.animations.get_mut(entity).is_some_and(|animation| {
+        animation.face(vec2(step.x, 0.0));
+        animation.update(dt, pos != transform.pos, 0.15)
+    });
+    if stepped {
+        world.footstep(pos + vec2(transform.size.x / 2.0, transform.size.y));
+    }
+}
+
+/// Walks along a path to `target` around blocks and water, searching a new
+/// one every `PATH_REFRESH` seconds. Returns `None` to walk straight at the
+/// target, e.g. once on its tile or when there is no path.
+fn chase(world: &mut World, entity: Entity, pos: Vec2, target: Vec2, dt: f32, reach: f32) -> Option<Vec2> {
+    let outdated = world.ecs.paths.get(entity).is_none_or(|path| path.age >= PATH_REFRESH);
+    if outdated {
+        match pathfinding::find_path(world, pos, target, true) {
+            Some(waypoints) => world.ecs.paths.insert(entity, Path::new(waypoints)),
+            None => {
+                world.ecs.paths.remove(entity);
+                return None;
+            }
+        }
+    }
+    let path = world.ecs.paths.get_mut(entity)?;
+    path.age += dt;
+    path.direction(pos, reach)
+}
+
+/// Mobs stay on land, so water counts as solid for them.
+fn solids_near(world: &World, rect: Rect) -> Vec<Rect> {
+    let radius = rect.w.max(rect.h) + 64.0;
+    let mut solids = world.solid_rects_near(rect.center(), radius);
+    solids.extend(world.tile_rects_near(rect.center(), radius, |tile| {
+        tile.is_some_and(|tile| tile.state == TileState::Water)
+    }));
+    solids
+}
+
+/// Whether a mob fits at `rect` without overlapping water or anything solid.
+pub fn can_stand(world: &World, rect: Rect) -> bool {
+    !solids_near(world, rect).iter().any(|solid| collision::intersects(solid, &rect))
+}
+
+pub fn draw(ecs: &Ecs, entity: Entity, transform: &Transform, kind: &str, textures: &MobTextures) {
+    let Some(sprite) = textures.sprites.get(kind) else {
+        return;
+    };
+    let hurt = ecs.healths.get(entity).is_some_and(|health| health.is_hurt());
+    let (frame, facing_left) = ecs.animations.get(entity)
+        .map(|animation| (animation.frame, animation.facing == Direction::Left))
+        .unwrap_or((0, false));
+    let (pos, size) = (transform.pos, transform.size);
+
+    match &sprite.texture {
+        Some(texture) => {
+            let frame_width = texture.width() / sprite.frames as f32;
+            let frame = (frame % sprite.frames) as f32;
+            draw_texture_ex(
+                texture,
+                pos.x,
+                pos.y,
+                if hurt { RED } else { WHITE },
+                DrawTextureParams {
+                    dest_size: Some(size),
+                    source: Some(Rect::new(frame * frame_width, 0.0, frame_width, texture.height())),
+                    flip_x: facing_left,
+                    ..Default::default()
+                }
+            );
+        }
+        None => {
+            let bob = if frame % 2 == 1 { 2.0 } else { 0.0 };
+            let color = if hurt { RED } else { sprite.color };
+            draw_rectangle(pos.x, pos.y - bob, size.x, size.y, color);
+
+            let eye_x = if facing_left { pos.x + 3.0 } else { pos.x + size.x - 7.0 };
+            draw_rectangle(eye_x, pos.y + 5.0 - bob, 4.0, 4.0, BLACK);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod ai;
+pub mod mob;
+pub mod registry;
+pub mod spawner;
+pub mod textures;
\ No newline at end of file
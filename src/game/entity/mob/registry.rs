@@ -0,0 +1,94 @@
+use std::{error::Error, fs};
+
+use game_core::MobMod;
+use macroquad::rand::gen_range;
+
+use crate::game::{item::{inventory::ItemStack, item::Item}, world::generator::biome::Biome};
+
+pub struct MobType {
+    pub config: MobMod,
+    pub drops: Vec<ItemStack>,
+}
+
+impl MobType {
+    /// Whether the mob can spawn in `biome` at this time of day.
+    pub fn spawns_in(&self, biome: &Biome, night: bool) -> bool {
+        if self.config.night != night {
+            return false;
+        }
+        let name = match biome {
+            Biome::Custom(id) => id.clone(),
+            biome => format!("{:?}", biome),
+        };
+        self.config.biomes.is_empty() || self.config.biomes.contains(&name)
+    }
+}
+
+/// Every known mob: the built-in ones followed by those from mods.
+#[derive(Default)]
+pub struct MobRegistry {
+    pub types: Vec<MobType>,
+}
+
+impl MobRegistry {
+    pub fn load(filename: &str, custom_mobs: &[MobMod]) -> Self {
+        let mut registry = Self::default();
+        match Self::read(filename) {
+            Ok(mobs) => registry.add(&mobs),
+            Err(e) => println!("Failed to load mobs from {}: {}", filename, e),
+        }
+        registry.add(custom_mobs);
+        registry
+    }
+
+    fn read(filename: &str) -> Result<Vec<MobMod>, Box<dyn Error>> {
+        let json = fs::read_to_string(filename)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Mobs with the id of an existing one replace it. Unknown drops are
+    /// left out.
+    pub fn add(&mut self, mobs: &[MobMod]) {
+        for mob in mobs {
+            let drops = mob.drops.iter()
+                .filter_map(|drop| match Item::from_id(&drop.item) {
+                    Some(item) => Some(ItemStack::new(item, drop.count)),
+                    None => {
+                        println!("Unknown drop {} of mob {}", drop.item, mob.id);
+                        None
+                    }
+                })
+                .collect();
+            let mob_type = MobType { config: mob.clone(), drops };
+            match self.types.iter_mut().find(|existing| existing.config.id == mob.id) {
+                Some(existing) => *existing = mob_type,
+                None => self.types.push(mob_type),
+            }
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&MobType> {
+        self.types.iter().find(|mob_type| mob_type.config.id == id)
+    }
+
+    /// Picks a random mob that can spawn in `biome`, weighted by their
+    /// spawn weights.
+    pub fn pick(&self, biome: &Biome, night: bool) -> Option<&MobType> {
+        let candidates: Vec<&MobType> = self.types.iter()
+            .filter(|mob_type| mob_type.config.weight > 0 && mob_type.spawns_in(biome, night))
+            .collect();
+        let total: u32 = candidates.iter().map(|mob_type| mob_type.config.weight).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = gen_range(0, total);
+        for mob_type in candidates {
+            if roll < mob_type.config.weight {
+                return Some(mob_type);
+            }
+            roll -= mob_type.config.weight;
+        }
+        None
+    }
+}
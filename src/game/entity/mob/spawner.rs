@@ -0,0 +1,56 @@
+use macroquad::{math::{vec2, Rect, Vec2}, rand::{gen_range, ChooseRandom}};
+
+use crate::{game::world::{chunk::chunk::ChunkPos, tile::state::TileState, world::World}, utils::consts::{CHUNK_SIZE, MAX_MOBS, MOBS_PER_CHUNK, MOB_SPAWN_DISTANCE, MOB_SPAWN_INTERVAL, TILE_SIZE}};
+
+use super::mob;
+
+/// Spawns mobs in loaded chunks away from the players, picking the mob by
+/// the spawn rules of each `MobType`.
+#[derive(Default)]
+pub struct MobSpawner {
+    timer: f32,
+}
+
+impl MobSpawner {
+    pub fn update(&mut self, dt: f32, world: &mut World, players: &[Vec2]) {
+        self.timer += dt;
+        if self.timer < MOB_SPAWN_INTERVAL {
+            return;
+        }
+        self.timer = 0.0;
+
+        if world.mob_count() >= MAX_MOBS {
+            return;
+        }
+        let chunks: Vec<ChunkPos> = world.chunks.values()
+            .filter(|chunk| chunk.mobs.len() < MOBS_PER_CHUNK)
+            .map(|chunk| chunk.pos)
+            .collect();
+        let Some(&(chunk_x, chunk_y)) = chunks.choose() else {
+            return;
+        };
+
+        let tile_x = chunk_x * CHUNK_SIZE + gen_range(0, CHUNK_SIZE);
+        let tile_y = chunk_y * CHUNK_SIZE + gen_range(0, CHUNK_SIZE);
+        let pos = vec2(tile_x as f32, tile_y as f32) * TILE_SIZE;
+        if players.iter().any(|player| player.distance(pos) < MOB_SPAWN_DISTANCE) {
+            return;
+        }
+
+        let Some(tile) = world.tile_at(pos) else {
+            return;
+        };
+        if tile.state == TileState::Water {
+            return;
+        }
+        let Some(mob_type) = world.mob_types.pick(&tile.biome, world.is_night()) else {
+            return;
+        };
+
+        let size = mob_type.config.size;
+        if mob::can_stand(world, Rect::new(pos.x, pos.y, size, size)) {
+            let kind = mob_type.config.id.clone();
+            world.spawn_mob(&kind, pos);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use macroquad::prelude::*;
+
+use super::registry::MobRegistry;
+
+pub struct MobSprite {
+    pub texture: Option<Texture2D>,
+    pub frames: u32,
+    pub color: Color,
+}
+
+/// Sprites of every mob type by id. Mobs without a texture are drawn as a
+/// colored shape.
+#[derive(Default)]
+pub struct MobTextures {
+    pub sprites: HashMap<String, MobSprite>,
+}
+
+impl MobTextures {
+    pub async fn load(registry: &MobRegistry) -> Self {
+        let mut sprites = HashMap::new();
+        for mob_type in &registry.types {
+            let config = &mob_type.config;
+            let texture = match &config.texture_path {
+                Some(path) => match load_texture(path).await {
+                    Ok(texture) => Some(texture),
+                    Err(e) => {
+                        println!("Failed to load texture for mob {}: {}", config.id, e);
+                        None
+                    }
+                },
+                None => None,
+            };
+            let [r, g, b, a] = config.color;
+            sprites.insert(config.id.clone(), MobSprite {
+                texture,
+                frames: config.frames.max(1),
+                color: Color::new(r, g, b, a),
+            });
+        }
+        Self { sprites }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod player;
-pub mod block;
\ No newline at end of file
+pub mod block;
+pub mod mob;
\ No newline at end of file
@@ -1,5 +1,7 @@
 use macroquad::prelude::*;
 
+use crate::{game::{ecs::components::Health, utils::animation::SpriteSheet, entity::block::{block::BlockType, textures::BlockTextures}, item::item::Item, world::tile::{state::TileState, textures::TileTextures}}, input::action::Action, utils::consts::{HOTBAR_SIZE, PLAYER_AIR}};
+
 use super::player::Player;
 
 pub struct PlayerGui {
@@ -17,11 +19,87 @@ impl PlayerGui {
         }
     }
     
-    pub fn draw_hearts(&self, player: &Player) {
+    /// Icons of animated items are drawn at their first frame.
+    pub fn item_icon<'a>(item: &Item, tiles: &'a TileTextures, blocks: &'a BlockTextures) -> Option<&'a SpriteSheet> {
+        match item {
+            Item::Tile(TileState::Grass) => Some(&tiles.grass),
+            Item::Tile(TileState::Tilled) => Some(&tiles.tilled),
+            Item::Tile(TileState::Sand) => Some(&tiles.sand),
+            Item::Tile(TileState::SnowGrass) => Some(&tiles.snow_grass),
+            Item::Tile(TileState::Water) => Some(&tiles.water),
+            Item::Tile(TileState::Custom(id)) => tiles.custom.get(id),
+            Item::Block(BlockType::Tree) => Some(&blocks.tree_top),
+            Item::Block(BlockType::Cactus) | Item::CactusPiece => Some(&blocks.cactus),
+            Item::Wood => Some(&blocks.tree),
+            Item::Hoe | Item::Shovel | Item::Seeds | Item::Wheat | Item::Meat | Item::Block(BlockType::Crop | BlockType::Torch | BlockType::Campfire) => None,
+        }
+    }
+
+    /// Draws the hotbar slots centered at the bottom of the screen, with the
+    /// name of the held item above them.
+    pub fn draw_hotbar(&self, player: &Player, tiles: &TileTextures, blocks: &BlockTextures) {
+        let slot_size = 36.0;
+        let padding = 4.0;
+        let width = HOTBAR_SIZE as f32 * (slot_size + padding) - padding;
+        let start_x = (screen_width() - width) / 2.0;
+        let y = screen_height() - slot_size - 10.0;
+
+        for slot in 0..HOTBAR_SIZE {
+            let x = start_x + slot as f32 * (slot_size + padding);
+            draw_rectangle(x, y, slot_size, slot_size, Color::new(0.0, 0.0, 0.0, 0.5));
+            let border = if slot == player.selected_slot { WHITE } else { GRAY };
+            draw_rectangle_lines(x, y, slot_size, slot_size, 2.0, border);
+
+            let Some(stack) = player.inventory.get(slot) else {
+                continue;
+            };
+            match Self::item_icon(&stack.item, tiles, blocks) {
+                Some(icon) => icon.draw(vec2(x + 6.0, y + 6.0), vec2(slot_size - 12.0, slot_size - 12.0), 0.0),
+                None => {
+                    draw_text(&stack.item.name()[..1], x + 12.0, y + 25.0, 24.0, WHITE);
+                }
+            }
+            if stack.count > 1 {
+                draw_text(&stack.count.to_string(), x + 3.0, y + slot_size - 3.0, 16.0, WHITE);
+            }
+        }
+
+        if let Some(item) = player.selected_item() {
+            let name = item.name();
+            let name_width = measure_text(&name, None, 20, 1.0).width;
+            draw_text(&name, (screen_width() - name_width) / 2.0, y - 8.0, 20.0, WHITE);
+        }
+    }
+
+    /// Breath bar above the hearts, only shown while under water.
+    pub fn draw_air(&self, player: &Player) {
+        if player.air >= PLAYER_AIR {
+            return;
+        }
+        let width = 100.0;
+        let y = screen_height() - 44.0;
+        let fraction = player.air.max(0.0) / PLAYER_AIR;
+        draw_rectangle(10.0, y, width, 6.0, Color::new(0.0, 0.0, 0.0, 0.5));
+        draw_rectangle(10.0, y, width * fraction, 6.0, SKYBLUE);
+    }
+
+    pub fn draw_death_screen(&self) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.5, 0.0, 0.0, 0.5));
+
+        let title = "You died";
+        let title_width = measure_text(title, None, 48, 1.0).width;
+        draw_text(title, (screen_width() - title_width) / 2.0, screen_height() / 2.0, 48.0, WHITE);
+
+        let hint = format!("{} to respawn", Action::UseItem.name());
+        let hint_width = measure_text(&hint, None, 24, 1.0).width;
+        draw_text(&hint, (screen_width() - hint_width) / 2.0, screen_height() / 2.0 + 40.0, 24.0, WHITE);
+    }
+
+    pub fn draw_hearts(&self, health: &Health) {
         let heart_size = 20.0;
-        let hearts_count = player.max_hp / 4;
+        let hearts_count = (health.max_hp + 1) / 2;
         let start_x = 10.0;
-        let start_y = screen_height() - heart_size - 10.0; 
+        let start_y = screen_height() - heart_size - 10.0;
         
         for i in 0..hearts_count {
             let x = start_x + (i as f32 * (heart_size + 2.0));
@@ -37,7 +115,7 @@ impl PlayerGui {
                 }
             );
             
-            let current_hp = player.hp - (i * 2);
+            let current_hp = health.hp - (i * 2);
             if current_hp >= 2 {
                 draw_texture_ex(
                     &self.hearts_full,
@@ -61,4 +139,4 @@ impl PlayerGui {
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -1,178 +1,256 @@
 use macroquad::prelude::*;
 
-use crate::game::{entity::block::block::Block, world::tile::{state::TileState, tile::Tile}};
+use crate::{game::{collision::collision, ecs::{components::{Animation, Health, Interpolation, Sprite, Transform, Velocity}, ecs::{Ecs, Entity}}, entity::block::block::BlockType, item::{inventory::Inventory, item::Item}, simulation::input::InputSnapshot, world::{tile::state::TileState, world::World}}, net::protocol::PlayerState, save::vec2::Vec2Save, utils::consts::{DROWN_DAMAGE, HOTBAR_SIZE, PLAYER_AIR, PLAYER_INVULNERABILITY, PLAYER_MAX_HP, PLAYER_SPEED, REGEN_DELAY, REGEN_INTERVAL}};
 
 use super::{direction::Direction, textures::PlayerTextures};
 
+pub const SIZE: Vec2 = vec2(32.0, 32.0);
+
+/// Marks an entity played by someone, here or over the network. Movement,
+/// health and drawing live in the other components.
 pub struct Player {
-    pub pos: Vec2,
-    pub size: f32,
-    pub hp: i32,
-    pub max_hp: i32,
-    pub direction: Direction,
-    pub is_moving: bool,
-    animation_frame: i32,
-    animation_timer: f32,
-    textures: PlayerTextures
+    /// Where the player comes back after dying.
+    pub spawn_point: Vec2,
+    /// Seconds of breath left in deep water.
+    pub air: f32,
+    regen_timer: f32,
+    pub inventory: Inventory,
+    /// Hotbar slot of the item in hand.
+    pub selected_slot: usize,
 }
 
 impl Player {
-    pub async fn new() -> Self {
-        let textures = PlayerTextures {
-            idle_up: load_texture("assets/textures/entities/player/idle_up.png").await.unwrap(),
-            idle_down: load_texture("assets/textures/entities/player/idle_down.png").await.unwrap(),
-            idle_left: load_texture("assets/textures/entities/player/idle_left.png").await.unwrap(),
-            idle_right: load_texture("assets/textures/entities/player/idle_right.png").await.unwrap(),
-            walk_up: [
-                load_texture("assets/textures/entities/player/walk_up_1.png").await.unwrap(),
-                load_texture("assets/textures/entities/player/walk_up_2.png").await.unwrap(),
-            ],
-            walk_down: [
-                load_texture("assets/textures/entities/player/walk_down_1.png").await.unwrap(),
-                load_texture("assets/textures/entities/player/walk_down_2.png").await.unwrap(),
-            ],
-            walk_left: [
-                load_texture("assets/textures/entities/player/walk_left_1.png").await.unwrap(),
-                load_texture("assets/textures/entities/player/walk_left_2.png").await.unwrap(),
-            ],
-            walk_right: [
-                load_texture("assets/textures/entities/player/walk_right_1.png").await.unwrap(),
-                load_texture("assets/textures/entities/player/walk_right_2.png").await.unwrap(),
-            ],
-        };
+    pub fn new(spawn_point: Vec2) -> Self {
         Player {
-            pos: vec2(512.0 * 32.0, 512.0 * 32.0),
-            size: 32.0,
-            direction: Direction::Down,
-            is_moving: false,
-                        
-            hp: 50,
-            max_hp: 50,
-            animation_frame: 0,
-            animation_timer: 0.0,
-            textures
+            spawn_point,
+            air: PLAYER_AIR,
+            regen_timer: 0.0,
+            inventory: Self::starting_inventory(),
+            selected_slot: 0,
         }
     }
-    pub fn update(&mut self, dt: f32, tiles: &[&Tile], blocks: &[&Box<dyn Block>]) {
-        let mut dx = 0.0;
-        let mut dy = 0.0;
-        
-        if is_key_down(KeyCode::W) { 
-            dy -= 1.0;}
-        if is_key_down(KeyCode::S) { dy += 1.0; }
-        if is_key_down(KeyCode::A) { dx -= 1.0; }
-        if is_key_down(KeyCode::D) { dx += 1.0; }
-        
-        if dx != 0.0 || dy != 0.0 {
-            if (dx as f32).abs() > (dy as f32).abs() {
-                self.direction = if dx > 0.0 { Direction::Right } else { Direction::Left };
-            } else {
-                self.direction = if dy > 0.0 { Direction::Down } else { Direction::Up };
-            }
-        }
-        
-        if dx != 0.0 && dy != 0.0 {
-            let len = ((dx * dx + dy * dy) as f32).sqrt();
-            dx /= len;
-            dy /= len;
-        }
-    
-        let base_speed = 2.0;
-        dx *= base_speed;
-        dy *= base_speed;
-        
-        let next_x = self.pos.x + dx;
-        let next_y = self.pos.y + dy;
-        
-        let mut can_move_x = true;
-        let mut can_move_y = true;
-        
-        for block in blocks.iter().filter(|block| {
-            let dx = (block.get_position().x - self.pos.x).abs();
-            let dy = (block.get_position().y - self.pos.y).abs();
-            dx < 64.0 && dy < 64.0
-        }) {
-            let buffer = 2.0; 
-
-            if next_x < block.get_position().x + block.get_size().x - buffer &&
-                next_x + self.size > block.get_position().x + buffer &&
-                self.pos.y < block.get_position().y + block.get_size().y - buffer &&
-                self.pos.y + self.size > block.get_position().y + buffer {
-                can_move_x = false;
-            }
 
-            if self.pos.x < block.get_position().x + block.get_size().x - buffer &&
-                self.pos.x + self.size > block.get_position().x + buffer &&
-                next_y < block.get_position().y + block.get_size().y - buffer &&
-                next_y + self.size > block.get_position().y + buffer {
-                can_move_y = false;
+    fn starting_inventory() -> Inventory {
+        let mut inventory = Inventory::default();
+        inventory.add(Item::Hoe, 1);
+        inventory.add(Item::Shovel, 1);
+        inventory.add(Item::Tile(TileState::Sand), 16);
+        inventory.add(Item::Tile(TileState::Water), 16);
+        inventory.add(Item::Block(BlockType::Tree), 4);
+        inventory.add(Item::Block(BlockType::Cactus), 4);
+        inventory.add(Item::Seeds, 4);
+        inventory
+    }
+
+    pub fn selected_item(&self) -> Option<&Item> {
+        self.inventory.get(self.selected_slot).map(|stack| &stack.item)
+    }
+
+    /// Moves the hotbar selection by `step` slots, wrapping around.
+    pub fn select_next(&mut self, step: i32) {
+        self.selected_slot = (self.selected_slot as i32 + step).rem_euclid(HOTBAR_SIZE as i32) as usize;
+    }
+}
+
+pub fn spawn(ecs: &mut Ecs, pos: Vec2) -> Entity {
+    let entity = ecs.spawn();
+    ecs.transforms.insert(entity, Transform::new(pos, SIZE));
+    ecs.velocities.insert(entity, Velocity(Vec2::ZERO));
+    ecs.interpolations.insert(entity, Interpolation::new(pos));
+    ecs.sprites.insert(entity, Sprite::Player);
+    ecs.animations.insert(entity, Animation::default());
+    ecs.healths.insert(entity, Health::new(PLAYER_MAX_HP));
+    ecs.players.insert(entity, Player::new(pos));
+    entity
+}
+
+pub fn update(world: &mut World, entity: Entity, dt: f32, input: &InputSnapshot) {
+    let Some(transform) = world.ecs.transforms.get(entity).copied() else {
+        return;
+    };
+    let pos = transform.pos;
+    let size = transform.size;
+    if let Some(interpolation) = world.ecs.interpolations.get_mut(entity) {
+        interpolation.prev_pos = pos;
+    }
+
+    let mut movement = input.movement;
+    if movement != Vec2::ZERO {
+        world.ecs.paths.remove(entity);
+    } else if let Some(path) = world.ecs.paths.get_mut(entity) {
+        match path.direction(transform.center(), PLAYER_SPEED * dt) {
+            Some(direction) => movement = direction,
+            None => {
+                world.ecs.paths.remove(entity);
             }
         }
-                
-        let tile_x = (self.pos.x / 32.0) as usize;
-        let tile_y = (self.pos.y / 32.0) as usize;
-        
-        if let Some(current_tile) = tiles.iter().find(|t|
-            (t.pos.x / 32.0) as usize == tile_x &&
-            (t.pos.y / 32.0) as usize == tile_y) {
-            let speed_multiplier = match current_tile.state {
-                TileState::Water => 0.5,
-                TileState::Sand => 0.9,
-                TileState::SnowGrass => 0.9,
-                _ => 1.0,
-            };
-            
-            if can_move_x { self.pos.x += dx * speed_multiplier; }
-            if can_move_y { self.pos.y += dy * speed_multiplier; }
+    }
+    if movement.length() > 1.0 {
+        movement = movement.normalize();
+    }
+
+    let speed_multiplier = world.tile_at(pos).map(|tile| tile.state.speed()).unwrap_or(1.0);
+    let velocity = movement * PLAYER_SPEED * speed_multiplier;
+    let solids = world.solid_rects_near(transform.center(), size.max_element() + 64.0);
+    let step = collision::move_and_slide(transform.rect(), velocity * dt, &solids);
+    let new_pos = pos + step;
+
+    if let Some(transform) = world.ecs.transforms.get_mut(entity) {
+        transform.pos = new_pos;
+    }
+    if let Some(velocity_component) = world.ecs.velocities.get_mut(entity) {
+        velocity_component.0 = velocity;
+    }
+    let stepped = world.ecs.animations.get_mut(entity).is_some_and(|animation| {
+        animation.face(movement);
+        animation.update(dt, step != Vec2::ZERO, 0.15)
+    });
+    if stepped {
+        world.footstep(new_pos + vec2(size.x / 2.0, size.y));
+    }
+}
+
+/// Returns `false` if the player is dead or still recovering from the
+/// last hit.
+pub fn damage(ecs: &mut Ecs, entity: Entity, amount: i32) -> bool {
+    let Some(health) = ecs.healths.get_mut(entity) else {
+        return false;
+    };
+    if amount <= 0 || health.is_dead() || health.is_hurt() {
+        return false;
+    }
+    health.damage(amount, PLAYER_INVULNERABILITY);
+    if let Some(player) = ecs.players.get_mut(entity) {
+        player.regen_timer = -REGEN_DELAY;
+    }
+    true
+}
+
+/// Drowning and regeneration. `deep_water` is whether the player is in
+/// water too deep to breathe.
+pub fn update_health(ecs: &mut Ecs, entity: Entity, dt: f32, deep_water: bool) {
+    if ecs.healths.get(entity).is_none_or(|health| health.is_dead()) {
+        return;
+    }
+    let Some(player) = ecs.players.get_mut(entity) else {
+        return;
+    };
+
+    let mut drowning = false;
+    if deep_water {
+        player.air -= dt;
+        drowning = player.air <= 0.0;
+    } else {
+        player.air = PLAYER_AIR;
+    }
+
+    player.regen_timer += dt;
+    let regenerate = player.regen_timer >= REGEN_INTERVAL;
+    if regenerate {
+        player.regen_timer -= REGEN_INTERVAL;
+    }
+
+    if drowning && damage(ecs, entity, DROWN_DAMAGE) {
+        if let Some(player) = ecs.players.get_mut(entity) {
+            player.air = 1.0;
         }
-            
-        if dx != 0.0 || dy != 0.0 {
-            self.is_moving = true;
-            self.animation_timer += dt;
-            if self.animation_timer > 0.15 {
-                self.animation_timer = 0.0;
-                self.animation_frame = (self.animation_frame + 1) % 3;
-            }
-        } else {
-            self.is_moving = false;
-            self.animation_frame = 1;
+    }
+    if regenerate {
+        if let Some(health) = ecs.healths.get_mut(entity) {
+            health.heal(1);
         }
     }
-    pub fn draw(&self) {
-        let texture = if self.is_moving {
-            match (&self.direction, self.animation_frame) {
-                (Direction::Up, 0) => &self.textures.walk_up[0],
-                (Direction::Up, 1) => &self.textures.idle_up,
-                (Direction::Up, 2) => &self.textures.walk_up[1],
-                (Direction::Down, 0) => &self.textures.walk_down[0],
-                (Direction::Down, 1) => &self.textures.idle_down,
-                (Direction::Down, 2) => &self.textures.walk_down[1],
-                (Direction::Left, 0) => &self.textures.walk_left[0],
-                (Direction::Left, 1) => &self.textures.idle_left,
-                (Direction::Left, 2) => &self.textures.walk_left[1],
-                (Direction::Right, 0) => &self.textures.walk_right[0],
-                (Direction::Right, 1) => &self.textures.idle_right,
-                (Direction::Right, 2) => &self.textures.walk_right[1],
-                _ => &self.textures.idle_down,
-            }
-        } else {
-            match self.direction {
-                Direction::Up => &self.textures.idle_up,
-                Direction::Down => &self.textures.idle_down,
-                Direction::Left => &self.textures.idle_left,
-                Direction::Right => &self.textures.idle_right,
-            }
-        };
-        
-        draw_texture_ex(
-            texture,
-            self.pos.x,
-            self.pos.y,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(self.size, self.size)),
-                ..Default::default()
-            }
-        );
+}
+
+/// Brings a dead player back at the spawn point with full health.
+pub fn respawn(ecs: &mut Ecs, entity: Entity) {
+    let Some(player) = ecs.players.get_mut(entity) else {
+        return;
+    };
+    player.air = PLAYER_AIR;
+    player.regen_timer = 0.0;
+    let spawn_point = player.spawn_point;
+
+    teleport(ecs, entity, spawn_point);
+    ecs.paths.remove(entity);
+    if let Some(health) = ecs.healths.get_mut(entity) {
+        health.hp = health.max_hp;
+        health.hurt_timer = 0.0;
+    }
+    if let Some(velocity) = ecs.velocities.get_mut(entity) {
+        velocity.0 = Vec2::ZERO;
+    }
+    if let Some(animation) = ecs.animations.get_mut(entity) {
+        animation.update(0.0, false, 0.0);
     }
 }
+
+pub fn teleport(ecs: &mut Ecs, entity: Entity, pos: Vec2) {
+    if let Some(transform) = ecs.transforms.get_mut(entity) {
+        transform.pos = pos;
+    }
+    ecs.interpolations.insert(entity, Interpolation::new(pos));
+}
+
+pub fn network_state(ecs: &Ecs, entity: Entity) -> Option<PlayerState> {
+    let animation = ecs.animations.get(entity)?;
+    Some(PlayerState {
+        pos: Vec2Save::from(ecs.transforms.get(entity)?.pos),
+        direction: animation.facing,
+        is_moving: animation.moving,
+        animation_frame: animation.frame as i32,
+    })
+}
+
+/// Used for players driven by another machine instead of `update`.
+pub fn apply_network_state(ecs: &mut Ecs, entity: Entity, state: &PlayerState) {
+    teleport(ecs, entity, Vec2::from(state.pos.clone()));
+    if let Some(animation) = ecs.animations.get_mut(entity) {
+        animation.facing = state.direction;
+        animation.moving = state.is_moving;
+        animation.frame = state.animation_frame.max(0) as u32;
+    }
+}
+
+pub fn draw(ecs: &Ecs, entity: Entity, pos: Vec2, textures: &PlayerTextures) {
+    let Some(animation) = ecs.animations.get(entity) else {
+        return;
+    };
+    let texture = if animation.moving {
+        match (&animation.facing, animation.frame % 3) {
+            (Direction::Up, 0) => &textures.walk_up[0],
+            (Direction::Up, 1) => &textures.idle_up,
+            (Direction::Up, _) => &textures.walk_up[1],
+            (Direction::Down, 0) => &textures.walk_down[0],
+            (Direction::Down, 1) => &textures.idle_down,
+            (Direction::Down, _) => &textures.walk_down[1],
+            (Direction::Left, 0) => &textures.walk_left[0],
+            (Direction::Left, 1) => &textures.idle_left,
+            (Direction::Left, _) => &textures.walk_left[1],
+            (Direction::Right, 0) => &textures.walk_right[0],
+            (Direction::Right, 1) => &textures.idle_right,
+            (Direction::Right, _) => &textures.walk_right[1],
+        }
+    } else {
+        match animation.facing {
+            Direction::Up => &textures.idle_up,
+            Direction::Down => &textures.idle_down,
+            Direction::Left => &textures.idle_left,
+            Direction::Right => &textures.idle_right,
+        }
+    };
+
+    let hurt = ecs.healths.get(entity).is_some_and(|health| health.is_hurt());
+    let tint = if hurt { Color::new(1.0, 0.5, 0.5, 1.0) } else { WHITE };
+    let size = ecs.transforms.get(entity).map(|transform| transform.size).unwrap_or(SIZE);
+    draw_texture_ex(
+        texture,
+        pos.x,
+        pos.y,
+        tint,
+        DrawTextureParams {
+            dest_size: Some(size),
+            ..Default::default()
+        }
+    );
+}
@@ -1,4 +1,4 @@
-use macroquad::texture::Texture2D;
+use macroquad::texture::{load_texture, Texture2D};
 
 pub struct PlayerTextures {
     pub idle_up: Texture2D,
@@ -10,3 +10,30 @@ pub struct PlayerTextures {
     pub walk_left: [Texture2D; 2],
     pub walk_right: [Texture2D; 2],
 }
+
+impl PlayerTextures {
+    pub async fn load() -> Self {
+        Self {
+            idle_up: load_texture("assets/textures/entities/player/idle_up.png").await.unwrap(),
+            idle_down: load_texture("assets/textures/entities/player/idle_down.png").await.unwrap(),
+            idle_left: load_texture("assets/textures/entities/player/idle_left.png").await.unwrap(),
+            idle_right: load_texture("assets/textures/entities/player/idle_right.png").await.unwrap(),
+            walk_up: [
+                load_texture("assets/textures/entities/player/walk_up_1.png").await.unwrap(),
+                load_texture("assets/textures/entities/player/walk_up_2.png").await.unwrap(),
+            ],
+            walk_down: [
+                load_texture("assets/textures/entities/player/walk_down_1.png").await.unwrap(),
+                load_texture("assets/textures/entities/player/walk_down_2.png").await.unwrap(),
+            ],
+            walk_left: [
+                load_texture("assets/textures/entities/player/walk_left_1.png").await.unwrap(),
+                load_texture("assets/textures/entities/player/walk_left_2.png").await.unwrap(),
+            ],
+            walk_right: [
+                load_texture("assets/textures/entities/player/walk_right_1.png").await.unwrap(),
+                load_texture("assets/textures/entities/player/walk_right_2.png").await.unwrap(),
+            ],
+        }
+    }
+}
@@ -1,290 +1,201 @@
 use std::error::Error;
 
-use game_core::{BiomeMod, TileMod};
+use game_core::{BiomeMod, EmitterMod, MobMod, RecipeMod, TileMod};
 use macroquad::prelude::*;
-use std::fs;
 
-use crate::{save::{chunk::ChunkSave, tile::TileSave, vec2::Vec2Save, world::WorldSave}, utils::{consts::{CHUNK_PIXELS, CHUNK_SIZE, DEFAULT_TILE, TILE_SIZE, WORLD_SIZE}, system::SystemInfo}};
+use crate::{input::{action::Action, input::Input}, net::client::NetClient, utils::{consts::{FIXED_TIMESTEP, MAX_FRAME_TIME, NET_VIEW_DISTANCE, SAVE_FILE}, settings::Settings, system::SystemInfo}};
 
-use super::{entity::{block::{block::{Block, BlockType}, blocks::{cactus::Cactus, tree::Tree}, textures::BlockTextures}, player::{gui::PlayerGui, player::Player}}, utils::draw::DrawBatch, world::{chunk::chunk::Chunk, generator::{biome::Biome, generator::WorldGenerator}, tile::{textures::TileTextures, tile::Tile}, world::World}};
+use super::{camera::camera::GameCamera, entity::player::player, renderer::renderer::Renderer, simulation::{input::InputSnapshot, simulation::Simulation}, world::world::World};
 
 pub struct GameState {
-    pub player: Player,
-    pub player_gui: PlayerGui,
-    pub camera: Camera2D,
-    pub world: World,
-    pub generator: WorldGenerator,
-    pub tile_textures: TileTextures,
-    pub block_textures: BlockTextures,
-    pub show_debug: bool,
-    pub custom_font: Font,
+    pub simulation: Simulation,
+    pub renderer: Renderer,
+    /// Connection to a server when playing multiplayer.
+    pub net: Option<NetClient>,
+    /// Stops the simulation. The world is still drawn and the connection
+    /// to a server kept alive.
+    pub paused: bool,
+    /// Clicking a tile out of reach walks there, see `Settings`.
+    pub click_to_move: bool,
+    accumulator: f32,
 }
 
 impl GameState {
-    pub async fn new(seed: u32, custom_biomes: Vec<BiomeMod>, custom_tiles: Vec<TileMod>) -> Self {
-        let player = Player::new().await;
-        let player_gui = PlayerGui::new().await;
-        let camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, screen_width(), -screen_height()));
-        let mut tile_textures = Tile::load_textures().await;
-        for tile in custom_tiles {
-            println!("Trying to load texture for tile: {} from: {}", tile.name, tile.texture_path);
-            if let Ok(texture) = load_texture(&tile.texture_path).await {
-                println!("Successfully loaded texture for: {}", tile.name);
-                tile_textures.custom.insert(tile.id, texture);
-            } else {
-                println!("Failed to load texture for: {}", tile.name);
-            }
-        }
-    
-        let block_textures = BlockTextures::load().await;
-        let mut generator = WorldGenerator::new(seed, WORLD_SIZE, WORLD_SIZE);
-        
-        for biome in custom_biomes {
-            generator.add_custom_biome(biome);
-        }
-        
-        let mut world = World::new(WORLD_SIZE, WORLD_SIZE);
-        
-        for chunk in &mut world.chunks {
-            *chunk = generator.generate_chunk(
-                chunk.pos.0,
-                chunk.pos.1,
-                tile_textures.clone(),
-                &block_textures
-            ).await;
-        }
-        
-        Self {
-            player,
-            player_gui,
-            camera,
-            world,
-            generator,
-            tile_textures,
-            block_textures,
-            show_debug: false,
-            custom_font: load_ttf_font("assets/bonspixels.ttf").await.expect("Failed to load font"),
-        }
+    pub async fn new(seed: u32, custom_biomes: Vec<BiomeMod>, custom_tiles: Vec<TileMod>, custom_recipes: Vec<RecipeMod>, custom_mobs: Vec<MobMod>, custom_emitters: Vec<EmitterMod>) -> Self {
+        let simulation = Simulation::new(seed, custom_biomes);
+        Self::with_simulation(simulation, custom_tiles, custom_recipes, custom_mobs, custom_emitters).await
     }
-    pub async fn update(&mut self, dt: f32, system_info: &SystemInfo) {
-        
-        self.world.cleanup_chunks();
-        self.world.update_chunks(self.player.pos, &mut self.generator, &self.tile_textures, &self.block_textures).await;
-         
-        let world_size = CHUNK_SIZE as f32 * 32.0 * 32.0;
-        self.update_camera(world_size);
-        
-        let visible_chunks: Vec<&Chunk> = self.world.chunks.iter()
-            .filter(|chunk| chunk.is_visible(self.camera.target, vec2(screen_width(), screen_height())))
-            .collect();
-            
-        self.draw_chunks(&visible_chunks);
-        let (tiles, blocks) = self.collect_visible_objects(&visible_chunks);
-        
-        self.player.update(dt, &tiles, &blocks);
-        self.player.draw();
+
+    /// Loads a world saved with `save_game`, with the same mods as a new one.
+    pub async fn load_game(filename: &str, custom_biomes: Vec<BiomeMod>, custom_tiles: Vec<TileMod>, custom_recipes: Vec<RecipeMod>, custom_mobs: Vec<MobMod>, custom_emitters: Vec<EmitterMod>) -> Result<Self, Box<dyn Error>> {
+        let simulation = Simulation::load(filename, custom_biomes)?;
+        Ok(Self::with_simulation(simulation, custom_tiles, custom_recipes, custom_mobs, custom_emitters).await)
+    }
+
+    async fn with_simulation(mut simulation: Simulation, custom_tiles: Vec<TileMod>, custom_recipes: Vec<RecipeMod>, custom_mobs: Vec<MobMod>, custom_emitters: Vec<EmitterMod>) -> Self {
+        simulation.add_mod_content(&custom_tiles, &custom_recipes, &custom_mobs);
+        simulation.world.record_effects = true;
+        let renderer = Renderer::new(custom_tiles, &custom_emitters, &simulation.world.mob_types, simulation.player_transform().pos).await;
         
-        if is_key_pressed(KeyCode::F3) {
-            self.show_debug = !self.show_debug;
-        }
-        if self.show_debug {
-            self.draw_debug(&tiles, system_info);
-        }
-        if is_key_pressed(KeyCode::F5) {
-            self.save_game("world");
+        Self {
+            simulation,
+            renderer,
+            net: None,
+            paused: false,
+            click_to_move: false,
+            accumulator: 0.0,
         }
     }
 
-    fn update_camera(&mut self, world_size: f32) {
-        self.camera.target = self.player.pos;
-        
-        let base_zoom = 0.0033334 / 3.0;
-        
-        let aspect_ratio = screen_width() / screen_height();
-        self.camera.zoom = if aspect_ratio > 1.0 {
-            vec2(base_zoom / aspect_ratio, base_zoom)
-        } else {
-            vec2(base_zoom, base_zoom * aspect_ratio)
-        };
-        
-        set_camera(&self.camera);
+    pub async fn join(address: &str, custom_biomes: Vec<BiomeMod>, custom_tiles: Vec<TileMod>, custom_recipes: Vec<RecipeMod>, custom_mobs: Vec<MobMod>, custom_emitters: Vec<EmitterMod>, mods: Vec<String>) -> Result<Self, Box<dyn Error>> {
+        let name = std::env::var("USER").unwrap_or_else(|_| "Player".to_owned());
+        let (net, welcome) = NetClient::connect(address, &name, mods)?;
+        println!("Joined {} as player {}", address, net.id);
+
+        let mut game = Self::new(welcome.seed, custom_biomes, custom_tiles, custom_recipes, custom_mobs, custom_emitters).await;
+        game.simulation.simulate_mobs = false;
+        game.simulation.simulate_weather = false;
+        player::teleport(&mut game.simulation.world.ecs, game.simulation.player, welcome.spawn);
+        game.simulation.player_mut().spawn_point = welcome.spawn;
+        game.simulation.world.clock = welcome.clock;
+        game.renderer.camera = GameCamera::new(welcome.spawn);
+        game.net = Some(net);
+        Ok(game)
     }
-    fn draw_chunks(&self, chunks: &[&Chunk]) {
-        let mut batch = DrawBatch::new();
-        
-        for chunk in chunks {
-            for tile in &chunk.tiles {
-                tile.draw(&chunk.tiles, CHUNK_SIZE, &mut batch);
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.renderer.camera.render_distance = settings.render_distance;
+        self.renderer.show_debug = settings.show_debug;
+        self.click_to_move = settings.click_to_move;
+    }
+
+    pub fn update(&mut self, dt: f32, input: &Input, system_info: &SystemInfo) {
+        if let Some(net) = &mut self.net {
+            if let Err(e) = net.poll(&mut self.simulation) {
+                println!("Disconnected from server: {}", e);
+                self.net = None;
             }
-            batch.draw();
-        
-            for block in &chunk.blocks {
-                block.draw();
+        }
+        match &self.net {
+            Some(_) => {
+                if let Some(player_chunk) = World::chunk_pos_at(self.simulation.player_transform().pos) {
+                    self.simulation.world.remove_far_chunks(&[player_chunk], NET_VIEW_DISTANCE);
+                }
             }
+            None => self.simulation.stream_chunks(self.renderer.camera.load_distance()),
         }
-    }
-
-    fn collect_visible_objects<'a>(&self, chunks: &'a [&'a Chunk]) -> (Vec<&'a Tile>, Vec<&'a Box<dyn Block>>) {
-        let mut tiles = Vec::new();
-        let mut blocks = Vec::new();
+         
+        let snapshot = if self.paused {
+            InputSnapshot::default()
+        } else {
+            InputSnapshot::capture(input)
+        };
+        if self.paused && self.net.is_none() {
+            self.accumulator = 0.0;
+        } else {
+            self.accumulator += dt.min(MAX_FRAME_TIME);
+        }
+        while self.accumulator >= FIXED_TIMESTEP {
+            self.simulation.step(FIXED_TIMESTEP, &snapshot);
+            self.accumulator -= FIXED_TIMESTEP;
+        }
+        self.simulation.interpolate(self.accumulator / FIXED_TIMESTEP);
 
-        for chunk in chunks {
-            tiles.extend(chunk.tiles.iter());
-            blocks.extend(chunk.blocks.iter());
+        if !self.paused {
+            self.handle_interaction(input);
         }
 
-        (tiles, blocks)
-    }
+        if let Some(net) = &mut self.net {
+            if let Err(e) = net.update(dt, &mut self.simulation) {
+                println!("Disconnected from server: {}", e);
+                self.net = None;
+            }
+        }
 
-    fn draw_debug(&self, all_tiles: &[&Tile], system_info: &SystemInfo) {
-        if !self.show_debug {
+        if !self.paused {
+            self.renderer.camera.handle_input(input);
+        }
+        self.renderer.particles.emit_all(self.simulation.world.effects.drain(..));
+        self.renderer.draw(&self.simulation, dt, system_info);
+        if self.paused {
             return;
         }
         
-        set_default_camera();
-        
-        let player_pos = self.get_player_position();
-        let current_tile = self.get_current_tile(all_tiles);
-        
-        self.draw_debug_info(player_pos, current_tile, system_info);
-        
-        set_camera(&self.camera);
-    }
-    
-    fn get_player_position(&self) -> (usize, usize, usize, usize) {
-        let player_tile_x = (self.player.pos.x / TILE_SIZE) as usize;
-        let player_tile_y = (self.player.pos.y / TILE_SIZE) as usize;
-        let player_chunk_x = (self.player.pos.x / CHUNK_PIXELS) as usize;
-        let player_chunk_y = (self.player.pos.y / CHUNK_PIXELS) as usize;
-        
-        (player_tile_x, player_tile_y, player_chunk_x, player_chunk_y)
-    }
-    
-    fn get_current_tile<'a>(&self, all_tiles: &'a [&'a Tile]) -> &'a Tile {
-        let (tile_x, tile_y, _, _) = self.get_player_position();
-        
-        all_tiles.iter()
-            .find(|t|
-                (t.pos.x / TILE_SIZE) as usize == tile_x &&
-                (t.pos.y / TILE_SIZE) as usize == tile_y
-            )
-            .copied()
-            .unwrap_or(&DEFAULT_TILE)
+        if input.is_pressed(Action::ToggleDebug) {
+            self.renderer.show_debug = !self.renderer.show_debug;
+        }
+        if input.is_pressed(Action::QuickSave) {
+            self.quick_save();
+        }
     }
 
-    fn draw_debug_info(&self, pos: (usize, usize, usize, usize), tile: &Tile, system_info: &SystemInfo) {
-        let lines = [
-            format!("Coords: ({:.2}, {:.2})", self.player.pos.x, self.player.pos.y),
-            format!("Chunk: ({}, {})", pos.2, pos.3),
-            format!("Loaded Chunks: {}", self.world.chunks.len()),
-            format!("Biome: {:?}", tile.biome),
-            format!("Seed: {}", self.generator.seed),
-            format!("RAM Usage: {} MB", system_info.process_memory),
-            format!("CPU Usage: {:.1}%", system_info.cpu_usage),
-            format!("FPS: {}", system_info.fps),
-        ];
-    
-        for (i, line) in lines.iter().enumerate() {
-            draw_text(line, 10.0, 20.0 + (i as f32 * 25.0), 20.0, WHITE);
+    fn handle_interaction(&mut self, input: &Input) {
+        if self.simulation.is_player_dead() {
+            self.renderer.target = None;
+            self.renderer.crafting_gui.open = false;
+            if input.is_pressed(Action::UseItem) {
+                self.simulation.respawn();
+            }
+            return;
         }
-    
-        self.draw_performance_indicator(
-            system_info.process_memory,
-            system_info.cpu_usage,
-            system_info.fps,
-            screen_width() - 150.0,
-            10.0
-        );
-    }
-    
-    fn draw_performance_indicator(&self, ram: u64, cpu: f32, fps: u32, x: f32, y: f32) {
-        let ram_color = match ram {
-            0..=512 => GREEN,
-            513..=1024 => YELLOW,
-            _ => RED,
-        };
-    
-        let cpu_color = match cpu as i32 {
-            0..=30 => GREEN,
-            31..=70 => YELLOW,
-            _ => RED,
-        };
-    
-        let fps_color = match fps {
-            60..=1000 => GREEN,
-            30..=59 => YELLOW,
-            _ => RED,
-        };
-    
-        draw_rectangle(x, y, 50.0, 10.0, ram_color);
-        draw_rectangle(x, y + 15.0, 50.0, 10.0, cpu_color);
-        draw_rectangle(x, y + 30.0, 50.0, 10.0, fps_color);
-    
-        draw_text(&format!("{} MB", ram), x + 60.0, y + 10.0, 20.0, ram_color);
-        draw_text(&format!("{:.1}% CPU", cpu), x + 60.0, y + 25.0, 20.0, cpu_color);
-        draw_text(&format!("{} FPS", fps), x + 60.0, y + 40.0, 20.0, fps_color);
-    }
-    pub fn save_game(&self, filename: &str) -> Result<(), Box<dyn Error>> {
-        let save = WorldSave {
-            seed: self.generator.seed,
-            player_pos: Vec2Save::from(self.player.pos),
-            chunks: self.world.chunks.iter()
-                .map(|chunk| ChunkSave {
-                    pos: chunk.pos,
-                    tiles: chunk.tiles.iter().map(TileSave::from).collect(),
-                    blocks: chunk.blocks.iter().map(|block| block.to_save()).collect(),
-                })
-                .collect()
-        };
-        
-        let json = serde_json::to_string(&save)?;
-        fs::write(filename, json)?;
-        Ok(())
-    }
-    pub async fn load_game(filename: &str) -> Result<Self, Box<dyn Error>> {
-        let json = fs::read_to_string(filename)?;
-        let save: WorldSave = serde_json::from_str(&json)?;
 
-        let mut game = Self::new(save.seed, Vec::new(), Vec::new()).await;
-        game.player.pos = Vec2::from(save.player_pos);
+        if input.is_pressed(Action::NextItem) {
+            self.simulation.player_mut().select_next(1);
+        }
+        if input.is_pressed(Action::PreviousItem) {
+            self.simulation.player_mut().select_next(-1);
+        }
 
-        game.world.chunks.clear();
-        for chunk_save in save.chunks {
-            let mut chunk = Chunk::new((chunk_save.pos.0, chunk_save.pos.1));
-            chunk.tiles = chunk_save.tiles.iter()
-                .map(|tile_save| Tile::from(tile_save.clone()))
-                .collect();
+        if input.is_pressed(Action::Crafting) {
+            self.renderer.crafting_gui.open = !self.renderer.crafting_gui.open;
+        }
+        if self.renderer.crafting_gui.open {
+            self.renderer.target = None;
+            if input.is_pressed(Action::UseItem) {
+                if let Some(recipe) = self.renderer.crafting_gui.recipe_at(Vec2::from(mouse_position()), &self.simulation.recipes) {
+                    self.simulation.craft(recipe);
+                }
+            }
+            return;
+        }
 
-            chunk.blocks = chunk_save.blocks.iter()
-                .map(|block_save| -> Box<dyn Block> {
-                    let tile_pos = (block_save.pos.x / 32.0) as usize;
-                    let tile_biome = chunk.tiles.iter()
-                        .find(|t| (t.pos.x / 32.0) as usize == tile_pos)
-                        .map(|t| &t.biome)
-                        .unwrap_or(&Biome::Forest);
+        let target = self.renderer.camera.mouse_world_pos();
+        self.renderer.target = Some(target).filter(|target| self.simulation.in_reach(*target));
 
-                    match block_save.block_type {
-                        BlockType::Cactus => {
-                            let mut cactus = Cactus::from_save(block_save.clone());
-                            cactus.set_texture(game.block_textures.cactus.clone());
-                            Box::new(cactus)
-                        },
-                        BlockType::Tree => {
-                            let mut tree = Tree::from_save(block_save.clone());
-                            tree.texture_bottom = Some(game.block_textures.tree.clone());
-                            tree.texture_top = Some(match tile_biome {
-                                Biome::SnowForest => game.block_textures.tree_snow_top.clone(),
-                                _ => game.block_textures.tree_top.clone(),
-                            });
-                            Box::new(tree)
-                        },
-                    }
-                })
-                .collect();
+        let edit = if input.is_pressed(Action::UseItem) && self.click_to_move && !self.simulation.in_reach(target) {
+            self.simulation.walk_to(target);
+            None
+        } else if input.is_pressed(Action::UseItem) {
+            self.simulation.use_item(target)
+        } else if input.is_pressed(Action::BreakBlock) {
+            self.simulation.break_block(target)
+        } else {
+            None
+        };
+        if let (Some(edit), Some(net)) = (edit, &mut self.net) {
+            if let Err(e) = net.send_edit(edit) {
+                println!("Disconnected from server: {}", e);
+                self.net = None;
+            }
+        }
+    }
 
-            game.world.chunks.push(chunk);
+    pub fn quick_save(&mut self) {
+        if self.net.is_some() {
+            println!("The world is saved by the server in multiplayer");
+        } else if let Err(e) = self.save_game(SAVE_FILE) {
+            println!("Failed to save game: {}", e);
         }
+    }
 
-        Ok(game)
+    pub fn save_game(&mut self, filename: &str) -> Result<(), Box<dyn Error>> {
+        self.simulation.save(filename)?;
+        self.simulation.unsaved_changes = false;
+        Ok(())
     }
-}
\ No newline at end of file
+
+    /// Changes that would be lost by quitting. Multiplayer worlds are saved
+    /// by the server.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.net.is_none() && self.simulation.unsaved_changes
+    }
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::consts::INVENTORY_SIZE;
+
+use super::item::Item;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub item: Item,
+    pub count: u32,
+}
+
+impl ItemStack {
+    pub fn new(item: Item, count: u32) -> Self {
+        Self { item, count }
+    }
+}
+
+/// Fixed number of slots; the first `HOTBAR_SIZE` of them make up the hotbar.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Inventory {
+    pub slots: Vec<Option<ItemStack>>,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            slots: vec![None; INVENTORY_SIZE],
+        }
+    }
+}
+
+impl Inventory {
+    pub fn get(&self, slot: usize) -> Option<&ItemStack> {
+        self.slots.get(slot)?.as_ref()
+    }
+
+    /// Fills existing stacks of the item first, then empty slots. Returns
+    /// how many did not fit.
+    pub fn add(&mut self, item: Item, mut count: u32) -> u32 {
+        let max_stack = item.max_stack();
+
+        for stack in self.slots.iter_mut().flatten() {
+            if count == 0 {
+                break;
+            }
+            if stack.item == item && stack.count < max_stack {
+                let added = count.min(max_stack - stack.count);
+                stack.count += added;
+                count -= added;
+            }
+        }
+
+        for slot in self.slots.iter_mut().filter(|slot| slot.is_none()) {
+            if count == 0 {
+                break;
+            }
+            let added = count.min(max_stack);
+            *slot = Some(ItemStack::new(item.clone(), added));
+            count -= added;
+        }
+        count
+    }
+
+    pub fn count(&self, item: &Item) -> u32 {
+        self.slots.iter()
+            .flatten()
+            .filter(|stack| stack.item == *item)
+            .map(|stack| stack.count)
+            .sum()
+    }
+
+    /// Removes `count` of the item from any slots, or nothing if there are
+    /// not enough.
+    pub fn remove(&mut self, item: &Item, mut count: u32) -> bool {
+        if self.count(item) < count {
+            return false;
+        }
+        for slot in self.slots.iter_mut() {
+            if count == 0 {
+                break;
+            }
+            if let Some(stack) = slot.as_mut().filter(|stack| stack.item == *item) {
+                let removed = count.min(stack.count);
+                stack.count -= removed;
+                count -= removed;
+                if stack.count == 0 {
+                    *slot = None;
+                }
+            }
+        }
+        true
+    }
+
+    /// Removes one item from `slot`. Returns `false` if the slot is empty.
+    pub fn take_one(&mut self, slot: usize) -> bool {
+        let Some(entry) = self.slots.get_mut(slot) else {
+            return false;
+        };
+        match entry {
+            Some(stack) => {
+                stack.count -= 1;
+                if stack.count == 0 {
+                    *entry = None;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::consts::MAX_STACK;
+
+    use super::*;
+
+    fn counts(inventory: &Inventory) -> Vec<u32> {
+        inventory.slots.iter().map(|slot| slot.as_ref().map_or(0, |stack| stack.count)).collect()
+    }
+
+    #[test]
+    fn overflows_into_the_next_slot() {
+        let mut inventory = Inventory::default();
+
+        assert_eq!(inventory.add(Item::Wood, MAX_STACK + 10), 0);
+        assert_eq!(counts(&inventory)[..3], [MAX_STACK, 10, 0]);
+    }
+
+    #[test]
+    fn tops_up_partial_stacks_before_empty_slots() {
+        let mut inventory = Inventory::default();
+        inventory.slots[2] = Some(ItemStack::new(Item::Wood, MAX_STACK - 5));
+        inventory.slots[4] = Some(ItemStack::new(Item::Wood, MAX_STACK - 5));
+
+        assert_eq!(inventory.add(Item::Wood, 12), 0);
+        assert_eq!(counts(&inventory)[..5], [2, 0, MAX_STACK, 0, MAX_STACK]);
+    }
+
+    #[test]
+    fn returns_what_does_not_fit() {
+        let mut inventory = Inventory::default();
+        inventory.add(Item::Hoe, INVENTORY_SIZE as u32 - 1);
+
+        assert_eq!(inventory.add(Item::Wood, MAX_STACK + 3), 3);
+        assert_eq!(inventory.add(Item::Hoe, 1), 1);
+        assert_eq!(inventory.count(&Item::Hoe), INVENTORY_SIZE as u32 - 1);
+    }
+
+    #[test]
+    fn removes_across_stacks_or_not_at_all() {
+        let mut inventory = Inventory::default();
+        inventory.slots[0] = Some(ItemStack::new(Item::Wood, 3));
+        inventory.slots[1] = Some(ItemStack::new(Item::Wood, 4));
+
+        assert!(!inventory.remove(&Item::Wood, 8));
+        assert_eq!(inventory.count(&Item::Wood), 7);
+        assert!(inventory.remove(&Item::Wood, 5));
+        assert!(inventory.slots[0].is_none());
+        assert_eq!(counts(&inventory)[1], 2);
+    }
+
+    #[test]
+    fn take_one_empties_the_slot() {
+        let mut inventory = Inventory::default();
+        inventory.add(Item::Seeds, 1);
+
+        assert!(inventory.take_one(0));
+        assert!(inventory.slots[0].is_none());
+        assert!(!inventory.take_one(0));
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{game::{entity::block::block::BlockType, world::tile::state::TileState}, utils::consts::MAX_STACK};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub enum Item {
+    /// Tills grass.
+    Hoe,
+    /// Digs up placed tiles, leaving the biome's ground.
+    Shovel,
+    Tile(TileState),
+    Block(BlockType),
+    Wood,
+    CactusPiece,
+    /// Planted on tilled soil.
+    Seeds,
+    Wheat,
+    /// Eaten to restore health.
+    Meat,
+}
+
+impl Item {
+    pub fn name(&self) -> String {
+        match self {
+            Item::Hoe => "Hoe".to_owned(),
+            Item::Shovel => "Shovel".to_owned(),
+            Item::Tile(TileState::Custom(id)) => id.clone(),
+            Item::Tile(TileState::SnowGrass) => "Snow grass".to_owned(),
+            Item::Tile(TileState::Grass) => "Grass".to_owned(),
+            Item::Tile(TileState::Tilled) => "Tilled soil".to_owned(),
+            Item::Tile(TileState::Sand) => "Sand".to_owned(),
+            Item::Tile(TileState::Water) => "Water".to_owned(),
+            Item::Block(BlockType::Tree) => "Tree".to_owned(),
+            Item::Block(BlockType::Cactus) => "Cactus".to_owned(),
+            Item::Block(BlockType::Crop) => "Crop".to_owned(),
+            Item::Block(BlockType::Torch) => "Torch".to_owned(),
+            Item::Block(BlockType::Campfire) => "Campfire".to_owned(),
+            Item::Seeds => "Seeds".to_owned(),
+            Item::Wheat => "Wheat".to_owned(),
+            Item::Meat => "Meat".to_owned(),
+            Item::Wood => "Wood".to_owned(),
+            Item::CactusPiece => "Cactus piece".to_owned(),
+        }
+    }
+
+    /// Id used by recipes, e.g. `wood`, `block:tree` or `tile:sand`.
+    pub fn id(&self) -> String {
+        match self {
+            Item::Hoe => "hoe".to_owned(),
+            Item::Shovel => "shovel".to_owned(),
+            Item::Wood => "wood".to_owned(),
+            Item::CactusPiece => "cactus_piece".to_owned(),
+            Item::Block(block_type) => format!("block:{}", block_type.id()),
+            Item::Seeds => "seeds".to_owned(),
+            Item::Wheat => "wheat".to_owned(),
+            Item::Meat => "meat".to_owned(),
+            Item::Tile(state) => format!("tile:{}", state.id()),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        let item = match id {
+            "hoe" => Item::Hoe,
+            "shovel" => Item::Shovel,
+            "wood" => Item::Wood,
+            "cactus_piece" => Item::CactusPiece,
+            "block:tree" => Item::Block(BlockType::Tree),
+            "block:cactus" => Item::Block(BlockType::Cactus),
+            "block:crop" => Item::Block(BlockType::Crop),
+            "block:torch" => Item::Block(BlockType::Torch),
+            "block:campfire" => Item::Block(BlockType::Campfire),
+            "seeds" => Item::Seeds,
+            "wheat" => Item::Wheat,
+            "meat" => Item::Meat,
+            "tile:grass" => Item::Tile(TileState::Grass),
+            "tile:tilled" => Item::Tile(TileState::Tilled),
+            "tile:sand" => Item::Tile(TileState::Sand),
+            "tile:snow_grass" => Item::Tile(TileState::SnowGrass),
+            "tile:water" => Item::Tile(TileState::Water),
+            _ => Item::Tile(TileState::Custom(id.strip_prefix("tile:")?.to_owned())),
+        };
+        Some(item)
+    }
+
+    pub fn max_stack(&self) -> u32 {
+        match self {
+            Item::Hoe | Item::Shovel => 1,
+            _ => MAX_STACK,
+        }
+    }
+}
@@ -0,0 +1,476 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::{game::world::{chunk::chunk::{Chunk, ChunkPos}, world::World}, utils::consts::{CHUNK_SIZE, MAX_LIGHT}};
+
+const CHUNK_TILES: usize = CHUNK_SIZE * CHUNK_SIZE;
+
+/// Revisions of a chunk and of the chunks left of and above it, whose blocks
+/// can reach into it. `None` where one isn't loaded.
+type Revisions = [Option<u64>; 4];
+
+/// World tile coordinates.
+type TilePos = (usize, usize);
+
+/// Light levels of the tiles of one chunk, row by row like its tiles.
+pub struct LightMap {
+    pub levels: Vec<u8>,
+}
+
+impl LightMap {
+    pub fn level(&self, local_x: usize, local_y: usize) -> u8 {
+        self.levels[Chunk::tile_index(local_x, local_y)]
+    }
+}
+
+/// What lights and shades the tiles of one chunk, copied out of the world
+/// row by row.
+struct ChunkLight {
+    opaque: Vec<bool>,
+    /// Light of the tile itself or of a block centered on it.
+    emission: Vec<u8>,
+}
+
+enum LightJob {
+    /// The chunk was loaded or it or a block reaching into it changed.
+    Update(ChunkPos, ChunkLight),
+    Unload(ChunkPos),
+}
+
+/// Light maps of the loaded chunks. They are computed on a worker thread,
+/// which keeps the light of the whole loaded world and only relights the
+/// tiles a change affects.
+pub struct Lighting {
+    maps: HashMap<ChunkPos, LightMap>,
+    /// Revisions each loaded chunk was last sent to the worker with.
+    sent: HashMap<ChunkPos, Revisions>,
+    jobs: Sender<LightJob>,
+    results: Receiver<Vec<(ChunkPos, LightMap)>>,
+}
+
+impl Default for Lighting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lighting {
+    /// Starts the worker thread. It stops once the `Lighting` is dropped.
+    pub fn new() -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<LightJob>();
+        let (result_sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            let mut grid = LightGrid::default();
+            while let Ok(job) = job_receiver.recv() {
+                // Everything that piled up is relit at once.
+                for job in std::iter::once(job).chain(job_receiver.try_iter()) {
+                    match job {
+                        LightJob::Update(pos, light) => grid.update(pos, light),
+                        LightJob::Unload(pos) => grid.unload(pos),
+                    }
+                }
+                if result_sender.send(grid.take_changed()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            maps: HashMap::new(),
+            sent: HashMap::new(),
+            jobs,
+            results,
+        }
+    }
+
+    /// Collects finished light maps and sends the worker the loaded chunks
+    /// that are new or changed, and those that were unloaded.
+    pub fn update(&mut self, world: &World) {
+        for (pos, map) in self.results.try_iter().flatten() {
+            if self.sent.contains_key(&pos) {
+                self.maps.insert(pos, map);
+            }
+        }
+
+        let unloaded: Vec<ChunkPos> = self.sent.keys().copied().filter(|pos| !world.chunks.contains_key(pos)).collect();
+        for pos in unloaded {
+            self.sent.remove(&pos);
+            self.maps.remove(&pos);
+            let _ = self.jobs.send(LightJob::Unload(pos));
+        }
+
+        for pos in world.chunks.keys() {
+            let revisions = revisions(world, *pos);
+            if self.sent.get(pos) == Some(&revisions) {
+                continue;
+            }
+            if self.jobs.send(LightJob::Update(*pos, chunk_light(world, *pos))).is_ok() {
+                self.sent.insert(*pos, revisions);
+            }
+        }
+    }
+
+    /// `None` until the chunk's first light map is done.
+    pub fn map(&self, pos: ChunkPos) -> Option<&LightMap> {
+        self.maps.get(&pos)
+    }
+
+    /// Light level of the tile at world tile coordinates, 0 if unknown.
+    pub fn level_at(&self, tile_x: usize, tile_y: usize) -> u8 {
+        self.map((tile_x / CHUNK_SIZE, tile_y / CHUNK_SIZE))
+            .map(|map| map.level(tile_x % CHUNK_SIZE, tile_y % CHUNK_SIZE))
+            .unwrap_or(0)
+    }
+}
+
+/// The chunk at `pos` and the chunks left of and above it.
+fn sources(pos: ChunkPos) -> impl Iterator<Item = ChunkPos> {
+    [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter()
+        .filter_map(move |(dx, dy)| pos.0.checked_sub(dx).zip(pos.1.checked_sub(dy)))
+}
+
+fn revisions(world: &World, pos: ChunkPos) -> Revisions {
+    let mut revisions = [None; 4];
+    for (revision, source) in revisions.iter_mut().zip(sources(pos)) {
+        *revision = world.chunks.get(&source).map(|chunk| chunk.revision);
+    }
+    revisions
+}
+
+fn chunk_light(world: &World, pos: ChunkPos) -> ChunkLight {
+    let mut light = ChunkLight {
+        opaque: vec![false; CHUNK_TILES],
+        emission: vec![0; CHUNK_TILES],
+    };
+    let Some(chunk) = world.chunks.get(&pos) else {
+        return light;
+    };
+    for (i, tile) in chunk.tiles.iter().enumerate().take(CHUNK_TILES) {
+        light.opaque[i] = world.is_solid_tile(Some(tile));
+        light.emission[i] = world.tile_light(tile);
+    }
+
+    // Blocks can reach into the next chunk, so they light the tile under
+    // their center.
+    let blocks = sources(pos).filter_map(|source| world.chunks.get(&source)).flat_map(|chunk| &chunk.blocks);
+    for entity in blocks {
+        let (Some(source), Some(transform)) = (world.ecs.lights.get(*entity), world.ecs.transforms.get(*entity)) else {
+            continue;
+        };
+        let Some((x, y)) = World::tile_coords_at(transform.center()) else {
+            continue;
+        };
+        if (x / CHUNK_SIZE, y / CHUNK_SIZE) == pos {
+            let i = Chunk::tile_index(x % CHUNK_SIZE, y % CHUNK_SIZE);
+            light.emission[i] = light.emission[i].max(source.0.min(MAX_LIGHT));
+        }
+    }
+    light
+}
+
+struct GridChunk {
+    light: ChunkLight,
+    levels: Vec<u8>,
+}
+
+/// Light of every loaded chunk, kept by the worker. Light loses one level
+/// per tile it travels and doesn't enter opaque tiles or unloaded chunks.
+#[derive(Default)]
+struct LightGrid {
+    chunks: HashMap<ChunkPos, GridChunk>,
+    /// Chunks whose levels changed since they were last taken.
+    changed: HashSet<ChunkPos>,
+}
+
+fn locate(pos: TilePos) -> (ChunkPos, usize) {
+    ((pos.0 / CHUNK_SIZE, pos.1 / CHUNK_SIZE), Chunk::tile_index(pos.0 % CHUNK_SIZE, pos.1 % CHUNK_SIZE))
+}
+
+fn chunk_tile(pos: ChunkPos, i: usize) -> TilePos {
+    (pos.0 * CHUNK_SIZE + i % CHUNK_SIZE, pos.1 * CHUNK_SIZE + i / CHUNK_SIZE)
+}
+
+fn neighbours(pos: TilePos) -> impl Iterator<Item = TilePos> {
+    [
+        (pos.0.checked_sub(1), Some(pos.1)),
+        (Some(pos.0 + 1), Some(pos.1)),
+        (Some(pos.0), pos.1.checked_sub(1)),
+        (Some(pos.0), Some(pos.1 + 1)),
+    ].into_iter().filter_map(|(x, y)| x.zip(y))
+}
+
+impl LightGrid {
+    fn level(&self, pos: TilePos) -> u8 {
+        let (chunk_pos, i) = locate(pos);
+        self.chunks.get(&chunk_pos).map_or(0, |chunk| chunk.levels[i])
+    }
+
+    fn set_level(&mut self, pos: TilePos, level: u8) {
+        let (chunk_pos, i) = locate(pos);
+        if let Some(chunk) = self.chunks.get_mut(&chunk_pos) {
+            chunk.levels[i] = level;
+            self.changed.insert(chunk_pos);
+        }
+    }
+
+    /// Loaded and lets light through.
+    fn is_open(&self, pos: TilePos) -> bool {
+        let (chunk_pos, i) = locate(pos);
+        self.chunks.get(&chunk_pos).is_some_and(|chunk| !chunk.light.opaque[i])
+    }
+
+    fn emission(&self, pos: TilePos) -> u8 {
+        let (chunk_pos, i) = locate(pos);
+        self.chunks.get(&chunk_pos).map_or(0, |chunk| chunk.light.emission[i])
+    }
+
+    /// Relights the tiles of the chunk that got darker or brighter. A newly
+    /// loaded chunk is lit from its own sources and its neighbours.
+    fn update(&mut self, pos: ChunkPos, light: ChunkLight) {
+        let mut darkened = Vec::new();
+        let mut brightened = Vec::new();
+        match self.chunks.get_mut(&pos) {
+            Some(chunk) => {
+                for i in 0..CHUNK_TILES {
+                    let (was_opaque, old_emission) = (chunk.light.opaque[i], chunk.light.emission[i]);
+                    let (opaque, emission) = (light.opaque[i], light.emission[i]);
+                    if (opaque && !was_opaque) || emission < old_emission {
+                        darkened.push(chunk_tile(pos, i));
+                    } else if (!opaque && was_opaque) || emission > old_emission {
+                        brightened.push(chunk_tile(pos, i));
+                    }
+                }
+                chunk.light = light;
+            }
+            None => {
+                self.chunks.insert(pos, GridChunk { light, levels: vec![0; CHUNK_TILES] });
+                self.changed.insert(pos);
+                brightened.extend((0..CHUNK_TILES).map(|i| chunk_tile(pos, i)));
+            }
+        }
+        self.relight(darkened, brightened);
+    }
+
+    /// Takes the chunk's light out of its neighbours before dropping it.
+    fn unload(&mut self, pos: ChunkPos) {
+        let Some(chunk) = self.chunks.get_mut(&pos) else {
+            return;
+        };
+        chunk.light.opaque.fill(true);
+        chunk.light.emission.fill(0);
+        let lit: Vec<TilePos> = (0..CHUNK_TILES)
+            .filter(|i| chunk.levels[*i] > 0)
+            .map(|i| chunk_tile(pos, i))
+            .collect();
+        self.relight(lit, Vec::new());
+        self.chunks.remove(&pos);
+        self.changed.remove(&pos);
+    }
+
+    /// Removes the light that reached the `darkened` tiles and everything it
+    /// lit further on, then spreads light back into the gap from the
+    /// sources left around it and into the `brightened` tiles.
+    fn relight(&mut self, darkened: Vec<TilePos>, brightened: Vec<TilePos>) {
+        let mut removal = VecDeque::new();
+        let mut seeds = brightened;
+        let mut spread = VecDeque::new();
+
+        for pos in darkened {
+            removal.push_back((pos, self.level(pos)));
+            self.set_level(pos, 0);
+            seeds.push(pos);
+        }
+        while let Some((pos, level)) = removal.pop_front() {
+            for next in neighbours(pos) {
+                let next_level = self.level(next);
+                if next_level == 0 {
+                    continue;
+                }
+                if next_level < level {
+                    // Lit by the removed light.
+                    self.set_level(next, 0);
+                    removal.push_back((next, next_level));
+                    if self.emission(next) > 0 {
+                        seeds.push(next);
+                    }
+                } else {
+                    // Lit by another source, which spreads back in.
+                    spread.push_back(next);
+                }
+            }
+        }
+
+        for pos in seeds {
+            let mut level = self.emission(pos);
+            if self.is_open(pos) {
+                let brightest = neighbours(pos).map(|next| self.level(next)).max().unwrap_or(0);
+                level = level.max(brightest.saturating_sub(1));
+            }
+            if level > self.level(pos) {
+                self.set_level(pos, level);
+                spread.push_back(pos);
+            }
+        }
+
+        while let Some(pos) = spread.pop_front() {
+            let level = self.level(pos);
+            if level <= 1 {
+                continue;
+            }
+            for next in neighbours(pos) {
+                if self.is_open(next) && self.level(next) < level - 1 {
+                    self.set_level(next, level - 1);
+                    spread.push_back(next);
+                }
+            }
+        }
+    }
+
+    fn take_changed(&mut self) -> Vec<(ChunkPos, LightMap)> {
+        self.changed.drain()
+            .filter_map(|pos| self.chunks.get(&pos).map(|chunk| (pos, LightMap { levels: chunk.levels.clone() })))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TORCH: u8 = 12;
+
+    fn dark() -> ChunkLight {
+        ChunkLight { opaque: vec![false; CHUNK_TILES], emission: vec![0; CHUNK_TILES] }
+    }
+
+    /// Grid of dark chunks from (0, 0) to (2, 2).
+    fn grid() -> LightGrid {
+        let mut grid = LightGrid::default();
+        for y in 0..3 {
+            for x in 0..3 {
+                grid.update((x, y), dark());
+            }
+        }
+        grid
+    }
+
+    /// Sets the tile's emission and opacity, as the world would send them.
+    fn set(grid: &mut LightGrid, pos: TilePos, emission: u8, opaque: bool) {
+        let (chunk_pos, i) = locate(pos);
+        let chunk = &grid.chunks[&chunk_pos].light;
+        let mut light = ChunkLight { opaque: chunk.opaque.clone(), emission: chunk.emission.clone() };
+        light.emission[i] = emission;
+        light.opaque[i] = opaque;
+        grid.update(chunk_pos, light);
+    }
+
+    /// Lights the grid from scratch, to compare incremental updates with.
+    fn expected(grid: &LightGrid) -> HashMap<TilePos, u8> {
+        let mut levels = HashMap::new();
+        for (pos, chunk) in &grid.chunks {
+            for i in 0..CHUNK_TILES {
+                levels.insert(chunk_tile(*pos, i), chunk.light.emission[i]);
+            }
+        }
+        loop {
+            let mut changed = false;
+            for pos in levels.keys().copied().collect::<Vec<_>>() {
+                if !grid.is_open(pos) {
+                    continue;
+                }
+                let brightest = neighbours(pos).filter_map(|next| levels.get(&next)).max().copied().unwrap_or(0);
+                if brightest.saturating_sub(1) > levels[&pos] {
+                    levels.insert(pos, brightest - 1);
+                    changed = true;
+                }
+            }
+            if !changed {
+                return levels;
+            }
+        }
+    }
+
+    fn assert_lit_correctly(grid: &LightGrid) {
+        for (pos, level) in expected(grid) {
+            assert_eq!(grid.level(pos), level, "wrong light at {pos:?}");
+        }
+    }
+
+    #[test]
+    fn light_crosses_chunk_boundaries() {
+        let mut grid = grid();
+        let torch = (CHUNK_SIZE * 2 - 2, CHUNK_SIZE + 3);
+        set(&mut grid, torch, TORCH, false);
+
+        assert_eq!(grid.level(torch), TORCH);
+        assert_eq!(grid.level((torch.0 + 3, torch.1)), TORCH - 3);
+        assert_lit_correctly(&grid);
+    }
+
+    #[test]
+    fn removing_a_source_leaves_other_light_alone() {
+        let mut grid = grid();
+        set(&mut grid, (20, 20), TORCH, false);
+        set(&mut grid, (26, 20), TORCH, false);
+        set(&mut grid, (20, 20), 0, false);
+
+        assert_eq!(grid.level((20, 20)), TORCH - 6);
+        assert_lit_correctly(&grid);
+
+        set(&mut grid, (26, 20), 0, false);
+        assert!(grid.chunks.values().all(|chunk| chunk.levels.iter().all(|level| *level == 0)));
+    }
+
+    #[test]
+    fn opaque_tiles_block_light_until_removed() {
+        let mut grid = grid();
+        set(&mut grid, (20, 20), TORCH, false);
+        for y in 10..=30 {
+            set(&mut grid, (22, y), 0, true);
+        }
+        assert_eq!(grid.level((22, 20)), 0);
+        assert!(grid.level((23, 20)) < TORCH - 3);
+        assert_lit_correctly(&grid);
+
+        set(&mut grid, (22, 20), 0, false);
+        assert_eq!(grid.level((23, 20)), TORCH - 3);
+        assert_lit_correctly(&grid);
+    }
+
+    #[test]
+    fn changes_only_touch_chunks_the_light_reaches() {
+        let mut grid = grid();
+        grid.take_changed();
+        set(&mut grid, (CHUNK_SIZE + 8, CHUNK_SIZE + 8), 4, false);
+
+        let changed: Vec<ChunkPos> = grid.take_changed().into_iter().map(|(pos, _)| pos).collect();
+        assert_eq!(changed, vec![(1, 1)]);
+    }
+
+    #[test]
+    fn unloading_takes_its_light_away() {
+        let mut grid = grid();
+        set(&mut grid, (CHUNK_SIZE - 1, 5), TORCH, false);
+        assert_eq!(grid.level((CHUNK_SIZE, 5)), TORCH - 1);
+
+        grid.unload((0, 0));
+        assert_eq!(grid.level((CHUNK_SIZE, 5)), 0);
+        assert_lit_correctly(&grid);
+
+        grid.update((0, 0), dark());
+        set(&mut grid, (CHUNK_SIZE - 1, 5), TORCH, false);
+        assert_eq!(grid.level((CHUNK_SIZE, 5)), TORCH - 1);
+    }
+
+    #[test]
+    fn loading_a_chunk_lets_light_flow_in() {
+        let mut grid = grid();
+        grid.unload((1, 0));
+        set(&mut grid, (CHUNK_SIZE - 1, 5), TORCH, false);
+        grid.update((1, 0), dark());
+
+        assert_eq!(grid.level((CHUNK_SIZE, 5)), TORCH - 1);
+        assert_lit_correctly(&grid);
+    }
+}
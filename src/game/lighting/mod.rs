@@ -0,0 +1 @@
+pub mod lighting;
\ No newline at end of file
@@ -1,4 +1,13 @@
+pub mod camera;
+pub mod collision;
+pub mod crafting;
+pub mod ecs;
 pub mod gamestate;
+pub mod item;
+pub mod lighting;
+pub mod pathfinding;
+pub mod renderer;
+pub mod simulation;
 pub mod utils;
 pub mod world;
-pub mod entity;
\ No newline at end of file
+pub mod entity;
\ No newline at end of file
@@ -0,0 +1,295 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use macroquad::math::{vec2, Vec2};
+
+use crate::{game::world::{chunk::chunk::ChunkPos, tile::state::TileState, world::World}, utils::consts::{CHUNK_SIZE, PATH_MAX_NODES, TILE_SIZE}};
+
+type TilePos = (i32, i32);
+
+const NEIGHBOURS: [TilePos; 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// Tile on the open list of the search. Ordered so the heap pops the lowest
+/// estimate first.
+struct Node {
+    estimate: f32,
+    pos: TilePos,
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl Eq for Node {}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+/// Which tiles can be walked on and how slow they are. Block colliders are
+/// gathered one chunk at a time, the first time the search gets there.
+struct Grid<'a> {
+    world: &'a World,
+    avoid_water: bool,
+    blocked: HashSet<TilePos>,
+    scanned: HashSet<ChunkPos>,
+}
+
+impl<'a> Grid<'a> {
+    fn new(world: &'a World, avoid_water: bool) -> Self {
+        Self { world, avoid_water, blocked: HashSet::new(), scanned: HashSet::new() }
+    }
+
+    /// Cost of walking one tile across `pos`, `None` if it can't be entered.
+    fn cost(&mut self, pos: TilePos) -> Option<f32> {
+        if pos.0 < 0 || pos.1 < 0 {
+            return None;
+        }
+        let world_pos = tile_center(pos);
+        let tile = self.world.tile_at(world_pos);
+        if self.world.is_solid_tile(tile) {
+            return None;
+        }
+        let state = &tile?.state;
+        if self.avoid_water && *state == TileState::Water {
+            return None;
+        }
+
+        // Blocks are stored in the chunk of their top left corner but may
+        // reach into the chunks right and below it.
+        let (chunk_x, chunk_y) = (pos.0 as usize / CHUNK_SIZE, pos.1 as usize / CHUNK_SIZE);
+        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            if chunk_x >= dx && chunk_y >= dy {
+                self.scan_chunk((chunk_x - dx, chunk_y - dy));
+            }
+        }
+        if self.blocked.contains(&pos) {
+            return None;
+        }
+        Some(1.0 / state.speed())
+    }
+
+    /// Marks every tile a block collider of the chunk covers.
+    fn scan_chunk(&mut self, chunk_pos: ChunkPos) {
+        if !self.scanned.insert(chunk_pos) {
+            return;
+        }
+        let Some(chunk) = self.world.chunks.get(&chunk_pos) else {
+            return;
+        };
+        let ecs = &self.world.ecs;
+        for entity in &chunk.blocks {
+            let (Some(collider), Some(transform)) = (ecs.colliders.get(*entity), ecs.transforms.get(*entity)) else {
+                continue;
+            };
+            let rect = collider.rect(transform);
+            let (min_x, min_y) = ((rect.left() / TILE_SIZE).floor() as i32, (rect.top() / TILE_SIZE).floor() as i32);
+            let (max_x, max_y) = (((rect.right() - 0.01) / TILE_SIZE).floor() as i32, ((rect.bottom() - 0.01) / TILE_SIZE).floor() as i32);
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    self.blocked.insert((x, y));
+                }
+            }
+        }
+    }
+}
+
+fn tile_of(world_pos: Vec2) -> TilePos {
+    ((world_pos.x / TILE_SIZE).floor() as i32, (world_pos.y / TILE_SIZE).floor() as i32)
+}
+
+fn tile_center(pos: TilePos) -> Vec2 {
+    vec2(pos.0 as f32 + 0.5, pos.1 as f32 + 0.5) * TILE_SIZE
+}
+
+/// Lower bound of the cost between two tiles when walking in eight directions.
+fn heuristic(a: TilePos, b: TilePos) -> f32 {
+    let dx = (a.0 - b.0).abs() as f32;
+    let dy = (a.1 - b.1).abs() as f32;
+    dx.max(dy) + (std::f32::consts::SQRT_2 - 1.0) * dx.min(dy)
+}
+
+/// A* search over the loaded tiles, from the tile at `start` to the tile at
+/// `goal`. Tiles covered by a block collider or solid are avoided, slow
+/// tiles cost more, and corners of blocked tiles aren't cut. Mobs pass
+/// `avoid_water` to stay on land. Returns the centers of the tiles to walk
+/// through, without the start, or `None` if there is no path within
+/// `PATH_MAX_NODES` tiles.
+pub fn find_path(world: &World, start: Vec2, goal: Vec2, avoid_water: bool) -> Option<Vec<Vec2>> {
+    let (start, goal) = (tile_of(start), tile_of(goal));
+    let mut grid = Grid::new(world, avoid_water);
+    grid.cost(goal)?;
+    if start == goal {
+        return Some(Vec::new());
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut costs: HashMap<TilePos, f32> = HashMap::new();
+    let mut came_from: HashMap<TilePos, TilePos> = HashMap::new();
+    open.push(Node { estimate: heuristic(start, goal), pos: start });
+    costs.insert(start, 0.0);
+
+    while let Some(Node { estimate, pos }) = open.pop() {
+        if pos == goal {
+            let mut path = vec![tile_center(pos)];
+            let mut current = pos;
+            while let Some(previous) = came_from.get(&current).filter(|previous| **previous != start) {
+                path.push(tile_center(*previous));
+                current = *previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        let cost = costs[&pos];
+        if estimate > cost + heuristic(pos, goal) {
+            // Stale entry, the tile was reached more cheaply since.
+            continue;
+        }
+        if costs.len() > PATH_MAX_NODES {
+            return None;
+        }
+
+        for (dx, dy) in NEIGHBOURS {
+            let next = (pos.0 + dx, pos.1 + dy);
+            let Some(tile_cost) = grid.cost(next) else {
+                continue;
+            };
+            let diagonal = dx != 0 && dy != 0;
+            if diagonal && (grid.cost((pos.0 + dx, pos.1)).is_none() || grid.cost((pos.0, pos.1 + dy)).is_none()) {
+                continue;
+            }
+
+            let distance = if diagonal { std::f32::consts::SQRT_2 } else { 1.0 };
+            let next_cost = cost + distance * tile_cost;
+            if costs.get(&next).is_none_or(|old| next_cost < *old) {
+                costs.insert(next, next_cost);
+                came_from.insert(next, pos);
+                open.push(Node { estimate: next_cost + heuristic(next, goal), pos: next });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{game::world::{chunk::chunk::Chunk, tile::tile::Tile}, utils::consts::WORLD_SIZE};
+
+    use super::*;
+
+    /// World with grass in the chunks from (0, 0) to `chunks` and a solid
+    /// `rock` mod tile.
+    fn grass_world(chunks: ChunkPos) -> World {
+        let mut world = World::new(WORLD_SIZE, WORLD_SIZE);
+        world.solid_tiles.insert("rock".to_owned());
+        for chunk_y in 0..chunks.1 {
+            for chunk_x in 0..chunks.0 {
+                let mut chunk = Chunk::new((chunk_x, chunk_y));
+                for y in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        let tile_x = chunk_x * CHUNK_SIZE + x;
+                        let tile_y = chunk_y * CHUNK_SIZE + y;
+                        chunk.tiles.push(Tile::new(tile_x as f32 * TILE_SIZE, tile_y as f32 * TILE_SIZE));
+                    }
+                }
+                world.chunks.insert((chunk_x, chunk_y), chunk);
+            }
+        }
+        world
+    }
+
+    fn set(world: &mut World, pos: TilePos, state: TileState) {
+        assert!(world.set_tile(tile_center(pos), state));
+    }
+
+    fn tiles(path: &[Vec2]) -> Vec<TilePos> {
+        path.iter().map(|pos| tile_of(*pos)).collect()
+    }
+
+    #[test]
+    fn routes_around_solid_tiles() {
+        let mut world = grass_world((1, 1));
+        for y in 0..12 {
+            set(&mut world, (8, y), TileState::Custom("rock".to_owned()));
+        }
+
+        let path = find_path(&world, tile_center((4, 2)), tile_center((12, 2)), false).unwrap();
+        let path = tiles(&path);
+        assert_eq!(path.last(), Some(&(12, 2)));
+        assert!(path.iter().all(|pos| pos.0 != 8 || pos.1 >= 12), "walked through the wall: {path:?}");
+        assert!(path.contains(&(8, 12)), "didn't take the gap: {path:?}");
+    }
+
+    #[test]
+    fn prefers_cheaper_tiles() {
+        let mut world = grass_world((1, 1));
+        for y in 4..=6 {
+            for x in 3..=7 {
+                set(&mut world, (x, y), TileState::Water);
+            }
+        }
+
+        let path = find_path(&world, tile_center((2, 5)), tile_center((8, 5)), false).unwrap();
+        let path = tiles(&path);
+        assert_eq!(path.last(), Some(&(8, 5)));
+        assert!(path.iter().all(|pos| !(3..=7).contains(&pos.0) || !(4..=6).contains(&pos.1)), "swam: {path:?}");
+    }
+
+    #[test]
+    fn crosses_chunk_boundaries() {
+        let world = grass_world((2, 2));
+        let goal = (CHUNK_SIZE as i32 + 3, CHUNK_SIZE as i32 + 5);
+
+        let path = find_path(&world, tile_center((2, 2)), tile_center(goal), false).unwrap();
+        let path = tiles(&path);
+        assert_eq!(path.last(), Some(&goal));
+        for pair in path.windows(2) {
+            assert!((pair[0].0 - pair[1].0).abs() <= 1 && (pair[0].1 - pair[1].1).abs() <= 1, "jumped: {path:?}");
+        }
+    }
+
+    #[test]
+    fn unreachable_goal_has_no_path() {
+        let mut world = grass_world((2, 2));
+        for (dx, dy) in NEIGHBOURS {
+            set(&mut world, (10 + dx, 10 + dy), TileState::Custom("rock".to_owned()));
+        }
+
+        assert!(find_path(&world, tile_center((2, 2)), tile_center((10, 10)), false).is_none());
+    }
+
+    #[test]
+    fn unloaded_goal_has_no_path() {
+        let world = grass_world((1, 1));
+        let goal = (CHUNK_SIZE as i32 + 2, 2);
+
+        assert!(find_path(&world, tile_center((2, 2)), tile_center(goal), false).is_none());
+    }
+
+    #[test]
+    fn gives_up_after_the_node_limit() {
+        // A wall with a gap far away at the bottom, so reaching the goal just
+        // behind it means flooding most of the area in front of it.
+        let mut world = grass_world((5, 5));
+        let side = 5 * CHUNK_SIZE as i32;
+        let wall = side / 2;
+        for y in 0..side - 1 {
+            set(&mut world, (wall, y), TileState::Custom("rock".to_owned()));
+        }
+        let (start, goal) = (tile_center((wall - 1, 0)), tile_center((wall + 1, 0)));
+
+        assert!(find_path(&world, start, goal, false).is_none());
+        set(&mut world, (wall, 1), TileState::Grass);
+        assert!(find_path(&world, start, goal, false).is_some());
+    }
+}
@@ -0,0 +1 @@
+pub mod renderer;
\ No newline at end of file
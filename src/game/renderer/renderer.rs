@@ -0,0 +1,337 @@
+use game_core::{EmitterMod, TileMod};
+use macroquad::prelude::*;
+
+use crate::{game::{camera::camera::GameCamera, crafting::gui::CraftingGui, lighting::lighting::Lighting, entity::{block::textures::BlockTextures, mob::{registry::MobRegistry, textures::MobTextures}, player::{gui::PlayerGui, textures::PlayerTextures}}, simulation::simulation::Simulation, utils::{animation::{AnimationClock, SpriteSheet}, draw::DrawBatch, particles::ParticleSystem, render::{DepthQueue, SpriteTextures}}, world::{chunk::chunk::Chunk, tile::{textures::TileTextures, tile::Tile}, weather::Precipitation, world::World}}, utils::{consts::{CHUNK_PIXELS, CHUNK_SIZE, DEFAULT_TILE, MAX_FRAME_TIME, MAX_LIGHT, MAX_PARTICLES, PARTICLES_FILE, TILE_SIZE, WEATHER_PARTICLES}, system::SystemInfo}};
+
+/// Color drawn over unlit tiles at midnight. Its alpha fades out with
+/// daylight and light from blocks.
+const NIGHT_TINT: Color = Color::new(0.02, 0.04, 0.15, 0.65);
+
+/// Draws a `Simulation` without changing it. Owns everything that needs a
+/// window: textures, fonts and the camera.
+pub struct Renderer {
+    pub camera: GameCamera,
+    pub tile_textures: TileTextures,
+    pub block_textures: BlockTextures,
+    pub player_textures: PlayerTextures,
+    pub mob_textures: MobTextures,
+    pub player_gui: PlayerGui,
+    pub crafting_gui: CraftingGui,
+    pub show_debug: bool,
+    pub custom_font: Font,
+    /// Point under the cursor when it is within the player's reach.
+    pub target: Option<Vec2>,
+    pub lighting: Lighting,
+    pub particles: ParticleSystem,
+    /// Shared by every animated tile and block so they stay in step.
+    pub animation_clock: AnimationClock,
+    /// How far rain, snow and sand have moved, wrapped around the view so
+    /// it keeps its precision in old worlds.
+    weather_scroll: Vec2,
+    /// Game time the weather was last drawn at.
+    weather_time: f64,
+}
+
+impl Renderer {
+    pub async fn new(custom_tiles: Vec<TileMod>, custom_emitters: &[EmitterMod], mob_types: &MobRegistry, focus: Vec2) -> Self {
+        let mut tile_textures = Tile::load_textures().await;
+        for tile in custom_tiles {
+            println!("Trying to load texture for tile: {} from: {}", tile.name, tile.texture_path);
+            if let Ok(texture) = load_texture(&tile.texture_path).await {
+                println!("Successfully loaded texture for: {}", tile.name);
+                tile_textures.custom.insert(tile.id, SpriteSheet::new(texture, tile.frames, tile.frame_time));
+            } else {
+                println!("Failed to load texture for: {}", tile.name);
+            }
+        }
+
+        Self {
+            camera: GameCamera::new(focus),
+            tile_textures,
+            block_textures: BlockTextures::load().await,
+            player_textures: PlayerTextures::load().await,
+            mob_textures: MobTextures::load(mob_types).await,
+            player_gui: PlayerGui::new().await,
+            crafting_gui: CraftingGui::default(),
+            show_debug: false,
+            custom_font: load_ttf_font("assets/bonspixels.ttf").await.expect("Failed to load font"),
+            target: None,
+            lighting: Lighting::new(),
+            particles: ParticleSystem::load(PARTICLES_FILE, MAX_PARTICLES, custom_emitters),
+            animation_clock: AnimationClock::default(),
+            weather_scroll: Vec2::ZERO,
+            weather_time: 0.0,
+        }
+    }
+
+    pub fn draw(&mut self, sim: &Simulation, dt: f32, system_info: &SystemInfo) {
+        self.update_camera(sim, dt);
+        self.animation_clock.advance(dt);
+        
+        let view = self.camera.view_rect();
+        let visible_chunks: Vec<&Chunk> = sim.world.chunks.values()
+            .filter(|chunk| chunk.is_visible(&view))
+            .collect();
+        self.lighting.update(&sim.world);
+
+        self.draw_chunks(sim, &visible_chunks, &view);
+        self.particles.update(dt);
+        self.particles.draw(&view);
+        self.draw_lighting(sim, &visible_chunks);
+        self.draw_weather(sim, &view);
+        self.draw_target();
+        self.draw_path(sim);
+        self.draw_hud(sim);
+        
+        if self.show_debug {
+            self.draw_debug(sim, system_info, visible_chunks.len());
+        }
+    }
+
+    fn update_camera(&mut self, sim: &Simulation, dt: f32) {
+        let size = sim.player_transform().size;
+        let player_center = sim.world.ecs.interpolations.get(sim.player)
+            .map(|interpolation| interpolation.render_pos + size / 2.0)
+            .unwrap_or_else(|| sim.player_center());
+        self.camera.update(dt, player_center);
+        
+        set_camera(&self.camera.camera);
+    }
+
+    fn draw_chunks(&self, sim: &Simulation, chunks: &[&Chunk], view: &Rect) {
+        let ecs = &sim.world.ecs;
+        let mut batch = DrawBatch::new();
+        let mut queue = DepthQueue::new();
+        let snow_line = sim.world.clock.season().snow_line();
+        
+        for chunk in chunks {
+            for tile in &chunk.tiles {
+                tile.draw(&self.tile_textures, &chunk.tiles, CHUNK_SIZE, snow_line, self.animation_clock.time, &mut batch);
+            }
+        
+            for entity in chunk.blocks.iter().chain(&chunk.mobs).filter(|entity| {
+                ecs.transforms.get(**entity).is_some_and(|transform| transform.rect().overlaps(view))
+            }) {
+                queue.add(ecs, *entity);
+            }
+        }
+        batch.draw();
+
+        queue.add(ecs, sim.player);
+        for player in sim.others.values().filter(|player| {
+            ecs.transforms.get(**player).is_some_and(|transform| view.contains(transform.pos))
+        }) {
+            queue.add(ecs, *player);
+        }
+        queue.draw(ecs, &SpriteTextures {
+            blocks: &self.block_textures,
+            mobs: Some(&self.mob_textures),
+            players: Some(&self.player_textures),
+            time: self.animation_clock.time,
+        });
+
+        for chunk in chunks {
+            for entity in &chunk.blocks {
+                let (Some(transform), Some(health)) = (ecs.transforms.get(*entity), ecs.healths.get(*entity)) else {
+                    continue;
+                };
+                if health.hp >= health.max_hp {
+                    continue;
+                }
+                let (pos, width) = (transform.pos, transform.size.x);
+                let fraction = health.hp.max(0) as f32 / health.max_hp as f32;
+                draw_rectangle(pos.x, pos.y - 6.0, width, 4.0, Color::new(0.0, 0.0, 0.0, 0.6));
+                draw_rectangle(pos.x, pos.y - 6.0, width * fraction, 4.0, GREEN);
+            }
+        }
+    }
+
+    /// Darkens tiles that neither daylight nor a light source reaches.
+    fn draw_lighting(&self, sim: &Simulation, chunks: &[&Chunk]) {
+        let daylight = sim.world.clock.daylight();
+        if daylight >= 1.0 {
+            return;
+        }
+        for chunk in chunks {
+            let map = self.lighting.map(chunk.pos);
+            let origin = vec2(chunk.pos.0 as f32, chunk.pos.1 as f32) * CHUNK_PIXELS;
+            for local_y in 0..CHUNK_SIZE {
+                for local_x in 0..CHUNK_SIZE {
+                    let light = map.map(|map| map.level(local_x, local_y)).unwrap_or(0) as f32 / MAX_LIGHT as f32;
+                    let darkness = 1.0 - daylight.max(light);
+                    if darkness <= 0.0 {
+                        continue;
+                    }
+                    let pos = origin + vec2(local_x as f32, local_y as f32) * TILE_SIZE;
+                    draw_rectangle(pos.x, pos.y, TILE_SIZE, TILE_SIZE, Color { a: NIGHT_TINT.a * darkness, ..NIGHT_TINT });
+                }
+            }
+        }
+    }
+
+    /// Draws rain, snow or sand over the view, depending on the weather
+    /// where the player is. Particles keep fixed offsets in the view and
+    /// move together by `weather_scroll`, which adds up the fall and drift
+    /// for the game time passed since `weather_time`.
+    fn draw_weather(&mut self, sim: &Simulation, view: &Rect) {
+        // Follows the game clock, so the weather stops while paused.
+        let dt = (sim.world.clock.time - self.weather_time).clamp(0.0, MAX_FRAME_TIME as f64) as f32;
+        self.weather_time = sim.world.clock.time;
+        let Some(precipitation) = sim.precipitation_at(sim.player_center()) else {
+            return;
+        };
+        let intensity = sim.world.weather.intensity;
+        let count = (WEATHER_PARTICLES as f32 * intensity) as usize;
+        let (speed, drift) = match precipitation {
+            Precipitation::Rain => (600.0, 80.0),
+            Precipitation::Snow => (60.0, 20.0),
+            Precipitation::Sandstorm => (40.0, 500.0),
+        };
+        self.weather_scroll = (self.weather_scroll + vec2(drift, speed) * dt).rem_euclid(view.size());
+        // Snowflakes sway with a sine, which repeats every full turn.
+        let sway = (sim.world.clock.time % std::f64::consts::TAU) as f32;
+
+        if precipitation == Precipitation::Sandstorm {
+            draw_rectangle(view.x, view.y, view.w, view.h, Color::new(0.8, 0.65, 0.4, 0.3 * intensity));
+        }
+        for i in 0..count {
+            // Fixed pseudo random offsets per particle.
+            let seed_x = (i as f32 * 12.9898).sin() * 43758.545;
+            let seed_y = (i as f32 * 78.233).sin() * 12543.164;
+            let x = view.x + (seed_x.fract().abs() * view.w + self.weather_scroll.x).rem_euclid(view.w);
+            let y = view.y + (seed_y.fract().abs() * view.h + self.weather_scroll.y).rem_euclid(view.h);
+
+            match precipitation {
+                Precipitation::Rain => draw_line(x, y, x + 2.0, y + 10.0, 1.0, Color::new(0.6, 0.7, 1.0, 0.6)),
+                Precipitation::Snow => draw_circle(x + (sway + i as f32).sin() * 6.0, y, 1.5, Color::new(1.0, 1.0, 1.0, 0.8)),
+                Precipitation::Sandstorm => draw_line(x, y, x + 12.0, y + 1.0, 1.0, Color::new(0.9, 0.75, 0.5, 0.6)),
+            }
+        }
+    }
+
+    fn draw_target(&self) {
+        if let Some((tile_x, tile_y)) = self.target.and_then(World::tile_coords_at) {
+            let pos = vec2(tile_x as f32, tile_y as f32) * TILE_SIZE;
+            draw_rectangle_lines(pos.x, pos.y, TILE_SIZE, TILE_SIZE, 2.0, Color::new(1.0, 1.0, 1.0, 0.7));
+        }
+    }
+
+    /// Marks the tiles the player is walking to after a click.
+    fn draw_path(&self, sim: &Simulation) {
+        let Some(path) = sim.world.ecs.paths.get(sim.player) else {
+            return;
+        };
+        for waypoint in path.waypoints() {
+            draw_circle(waypoint.x, waypoint.y, 3.0, Color::new(1.0, 1.0, 1.0, 0.5));
+        }
+    }
+
+    fn draw_hud(&self, sim: &Simulation) {
+        set_default_camera();
+
+        let player = sim.player();
+        self.player_gui.draw_hotbar(player, &self.tile_textures, &self.block_textures);
+        if let Some(health) = sim.world.ecs.healths.get(sim.player) {
+            self.player_gui.draw_hearts(health);
+        }
+        self.player_gui.draw_air(player);
+        self.crafting_gui.draw(&sim.recipes, &player.inventory, &self.tile_textures, &self.block_textures);
+        if sim.is_player_dead() {
+            self.player_gui.draw_death_screen();
+        }
+
+        set_camera(&self.camera.camera);
+    }
+
+    fn draw_debug(&self, sim: &Simulation, system_info: &SystemInfo, visible_chunks: usize) {
+        if !self.show_debug {
+            return;
+        }
+        
+        set_default_camera();
+        
+        let player_pos = self.get_player_position(sim.player_transform().pos);
+        let current_tile = self.get_current_tile(sim);
+        
+        self.draw_debug_info(sim, player_pos, current_tile, system_info, visible_chunks);
+        
+        set_camera(&self.camera.camera);
+    }
+    
+    fn get_player_position(&self, pos: Vec2) -> (usize, usize, usize, usize) {
+        let player_tile_x = (pos.x / TILE_SIZE) as usize;
+        let player_tile_y = (pos.y / TILE_SIZE) as usize;
+        let player_chunk_x = (pos.x / CHUNK_PIXELS) as usize;
+        let player_chunk_y = (pos.y / CHUNK_PIXELS) as usize;
+        
+        (player_tile_x, player_tile_y, player_chunk_x, player_chunk_y)
+    }
+    
+    fn get_current_tile<'a>(&self, sim: &'a Simulation) -> &'a Tile {
+        sim.world.tile_at(sim.player_transform().pos).unwrap_or(&DEFAULT_TILE)
+    }
+
+    fn draw_debug_info(&self, sim: &Simulation, pos: (usize, usize, usize, usize), tile: &Tile, system_info: &SystemInfo, visible_chunks: usize) {
+        let lines = [
+            format!("Coords: ({:.2}, {:.2})", sim.player_transform().pos.x, sim.player_transform().pos.y),
+            format!("Chunk: ({}, {})", pos.2, pos.3),
+            format!("Loaded Chunks: {}", sim.world.chunks.len()),
+            format!("Mobs: {}", sim.world.mob_count()),
+            format!("Particles: {}", self.particles.active_count()),
+            format!("Visible Chunks: {}", visible_chunks),
+            format!("Zoom: {:.2}x", self.camera.zoom),
+            format!("Biome: {:?}", tile.biome),
+            format!("Light: {}", self.lighting.level_at(pos.0, pos.1)),
+            format!("Season: {:?}", sim.world.clock.season()),
+            format!("Weather: {}", match sim.precipitation_at(sim.player_center()) {
+                Some(precipitation) => format!("{:?} ({:.0}%)", precipitation, sim.world.weather.intensity * 100.0),
+                None => "Clear".to_owned(),
+            }),
+            format!("Seed: {}", sim.generator.seed),
+            format!("Time: {}{}", sim.world.clock.display(), if sim.world.is_night() { " (night)" } else { "" }),
+            format!("RAM Usage: {} MB", system_info.process_memory),
+            format!("CPU Usage: {:.1}%", system_info.cpu_usage),
+            format!("FPS: {}", system_info.fps),
+        ];
+    
+        for (i, line) in lines.iter().enumerate() {
+            draw_text(line, 10.0, 20.0 + (i as f32 * 25.0), 20.0, WHITE);
+        }
+    
+        self.draw_performance_indicator(
+            system_info.process_memory,
+            system_info.cpu_usage,
+            system_info.fps,
+            screen_width() - 150.0,
+            10.0
+        );
+    }
+    
+    fn draw_performance_indicator(&self, ram: u64, cpu: f32, fps: u32, x: f32, y: f32) {
+        let ram_color = match ram {
+            0..=512 => GREEN,
+            513..=1024 => YELLOW,
+            _ => RED,
+        };
+    
+        let cpu_color = match cpu as i32 {
+            0..=30 => GREEN,
+            31..=70 => YELLOW,
+            _ => RED,
+        };
+    
+        let fps_color = match fps {
+            60..=1000 => GREEN,
+            30..=59 => YELLOW,
+            _ => RED,
+        };
+    
+        draw_rectangle(x, y, 50.0, 10.0, ram_color);
+        draw_rectangle(x, y + 15.0, 50.0, 10.0, cpu_color);
+        draw_rectangle(x, y + 30.0, 50.0, 10.0, fps_color);
+    
+        draw_text(&format!("{} MB", ram), x + 60.0, y + 10.0, 20.0, ram_color);
+        draw_text(&format!("{:.1}% CPU", cpu), x + 60.0, y + 25.0, 20.0, cpu_color);
+        draw_text(&format!("{} FPS", fps), x + 60.0, y + 40.0, 20.0, fps_color);
+    }
+}
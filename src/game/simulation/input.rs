@@ -0,0 +1,18 @@
+use macroquad::prelude::*;
+
+use crate::input::input::Input;
+
+/// Player input for one simulation step. Built from the action bindings in
+/// the game, or by hand when the simulation runs without a window.
+#[derive(Clone, Default)]
+pub struct InputSnapshot {
+    pub movement: Vec2,
+}
+
+impl InputSnapshot {
+    pub fn capture(input: &Input) -> Self {
+        Self {
+            movement: input.movement(),
+        }
+    }
+}
@@ -0,0 +1,570 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+
+use game_core::{BiomeMod, MobMod, RecipeMod, TileMod};
+use macroquad::{math::{vec2, Rect, Vec2}, rand::gen_range};
+
+use crate::{game::{crafting::crafting::RecipeBook, ecs::{components::{Path, Transform}, ecs::Entity, systems}, entity::{block::block::BlockType, mob::{registry::MobRegistry, spawner::MobSpawner}, player::player::{self, Player}}, item::{inventory::ItemStack, item::Item}, pathfinding::pathfinding, world::{clock::Clock, edit::WorldEdit, generator::generator::WorldGenerator, tile::state::TileState, weather::Precipitation, world::World}}, net::protocol::PlayerId, save::{block::BlockSave, chunk::ChunkSave, vec2::Vec2Save, world::WorldSave}, utils::consts::{CROP_TICK, MEAT_HEAL, MOBS_FILE, NET_REACH_SLACK, REACH_DISTANCE, RECIPES_FILE, SEED_CHANCE, TILE_SIZE, WORLD_SIZE}};
+
+use super::input::InputSnapshot;
+
+/// Game logic without any rendering or window access, so it can be stepped
+/// headless.
+pub struct Simulation {
+    pub world: World,
+    /// Entity of the player in `world.ecs`.
+    pub player: Entity,
+    /// Players controlled over the network, keyed by their connection id.
+    pub others: HashMap<PlayerId, Entity>,
+    pub generator: WorldGenerator,
+    pub recipes: RecipeBook,
+    pub mob_spawner: MobSpawner,
+    /// Mobs are only moved and spawned by whoever owns the world, clients
+    /// get them from the server.
+    pub simulate_mobs: bool,
+    /// Weather only changes where the world is owned, clients get it from
+    /// the server.
+    pub simulate_weather: bool,
+    /// `player` is played on this machine. The server has none and only
+    /// keeps `player` as the spawn point.
+    pub local_player: bool,
+    /// Damage of mod tiles that hurt players standing on them, by tile id.
+    pub tile_damage: HashMap<String, i32>,
+    /// Set when anything that is saved changed since the last save.
+    pub unsaved_changes: bool,
+    /// Time since crops last grew, they are only updated every `CROP_TICK`.
+    crop_timer: f32,
+}
+
+impl Simulation {
+    pub fn new(seed: u32, custom_biomes: Vec<BiomeMod>) -> Self {
+        let mut generator = WorldGenerator::new(seed, WORLD_SIZE, WORLD_SIZE);
+        
+        for biome in custom_biomes {
+            generator.add_custom_biome(biome);
+        }
+
+        let mut world = World::new(WORLD_SIZE, WORLD_SIZE);
+        world.mob_types = MobRegistry::load(MOBS_FILE, &[]);
+        let player = player::spawn(&mut world.ecs, vec2(512.0 * 32.0, 512.0 * 32.0));
+
+        Self {
+            world,
+            player,
+            others: HashMap::new(),
+            generator,
+            recipes: RecipeBook::load(RECIPES_FILE, &[]),
+            mob_spawner: MobSpawner::default(),
+            simulate_mobs: true,
+            simulate_weather: true,
+            local_player: true,
+            tile_damage: HashMap::new(),
+            unsaved_changes: false,
+            crop_timer: 0.0,
+        }
+    }
+
+    /// Loads chunks within `load_distance` chunks of any player and unloads the rest.
+    pub fn stream_chunks(&mut self, load_distance: f32) {
+        let positions: Vec<Vec2> = std::iter::once(&self.player)
+            .chain(self.others.values())
+            .filter_map(|player| self.world.ecs.transforms.get(*player).map(|transform| transform.pos))
+            .collect();
+        self.world.cleanup_chunks(load_distance, positions.len());
+        self.world.update_chunks(&positions, load_distance, &mut self.generator);
+    }
+
+    pub fn add_mobs(&mut self, mobs: &[MobMod]) {
+        self.world.mob_types.add(mobs);
+    }
+
+    pub fn player(&self) -> &Player {
+        self.world.ecs.players.get(self.player).expect("the local player is never despawned")
+    }
+
+    pub fn player_mut(&mut self) -> &mut Player {
+        self.world.ecs.players.get_mut(self.player).expect("the local player is never despawned")
+    }
+
+    pub fn player_transform(&self) -> Transform {
+        self.world.ecs.transforms.get(self.player).copied().expect("the local player is never despawned")
+    }
+
+    pub fn player_center(&self) -> Vec2 {
+        self.player_transform().center()
+    }
+
+    /// What the weather brings at `pos`, `None` while it is clear there.
+    pub fn precipitation_at(&self, pos: Vec2) -> Option<Precipitation> {
+        if self.world.weather.is_clear() {
+            return None;
+        }
+        let tile = self.world.tile_at(pos)?;
+        let (tile_x, tile_y) = World::tile_coords_at(pos)?;
+        let (moisture, _) = self.generator.climate_at(tile_x, tile_y);
+        let temperature = tile.temperature as f64 + self.world.clock.season().temperature_offset();
+        Precipitation::at(&tile.biome, moisture, temperature)
+    }
+
+    pub fn is_player_dead(&self) -> bool {
+        self.world.ecs.healths.get(self.player).is_none_or(|health| health.is_dead())
+    }
+
+    /// Adds a player controlled over the network.
+    pub fn add_other(&mut self, id: PlayerId, pos: Vec2) -> Entity {
+        let entity = player::spawn(&mut self.world.ecs, pos);
+        if let Some(old) = self.others.insert(id, entity) {
+            self.world.ecs.despawn(old);
+        }
+        entity
+    }
+
+    pub fn remove_other(&mut self, id: PlayerId) {
+        if let Some(entity) = self.others.remove(&id) {
+            self.world.ecs.despawn(entity);
+        }
+    }
+
+    /// Blends the last two simulation steps for drawing; `alpha` is how far
+    /// the renderer is into the next step.
+    pub fn interpolate(&mut self, alpha: f32) {
+        systems::interpolate(&mut self.world.ecs, alpha);
+    }
+
+    /// Registers the tiles, recipes and mobs of mods. Clients and the server
+    /// both do, so they agree on which tiles are solid or hurt.
+    pub fn add_mod_content(&mut self, tiles: &[TileMod], recipes: &[RecipeMod], mobs: &[MobMod]) {
+        self.recipes.add(recipes);
+        self.add_tile_hazards(tiles);
+        self.world.add_tile_properties(tiles);
+        self.add_mobs(mobs);
+    }
+
+    pub fn add_tile_hazards(&mut self, tiles: &[TileMod]) {
+        for tile in tiles.iter().filter(|tile| tile.damage > 0) {
+            self.tile_damage.insert(tile.id.clone(), tile.damage);
+        }
+    }
+
+    /// Advances the game logic by one fixed step, independent of the frame rate.
+    pub fn step(&mut self, dt: f32, input: &InputSnapshot) {
+        systems::update_hurt_timers(&mut self.world.ecs, dt);
+
+        if self.local_player && !self.is_player_dead() {
+            let old_pos = self.player_transform().pos;
+            player::update(&mut self.world, self.player, dt, input);
+            if self.player_transform().pos != old_pos {
+                self.unsaved_changes = true;
+            }
+            self.apply_hazards(dt);
+        }
+
+        if self.simulate_mobs {
+            let ecs = &self.world.ecs;
+            let players: Vec<Vec2> = std::iter::once(&self.player)
+                .filter(|_| self.local_player)
+                .chain(self.others.values())
+                .filter(|player| ecs.healths.get(**player).is_some_and(|health| !health.is_dead()))
+                .filter_map(|player| ecs.transforms.get(*player).map(|transform| transform.center()))
+                .collect();
+            self.world.update_mobs(dt, &players);
+            self.mob_spawner.update(dt, &mut self.world, &players);
+        }
+
+        self.world.clock.advance(dt);
+        if self.simulate_weather {
+            self.world.weather.update(dt);
+        }
+        self.crop_timer += dt;
+        if self.crop_timer >= CROP_TICK {
+            self.world.grow_blocks(self.crop_timer);
+            self.crop_timer = 0.0;
+        }
+    }
+
+    /// Hurts the player touching cacti or standing on dangerous tiles, and
+    /// lets them drown in deep water.
+    fn apply_hazards(&mut self, dt: f32) {
+        let transform = self.player_transform();
+        let reach = 2.0;
+        let player_rect = Rect::new(
+            transform.pos.x - reach, transform.pos.y - reach,
+            transform.size.x + reach * 2.0, transform.size.y + reach * 2.0,
+        );
+        let contact_damage = self.world.contact_damage(player_rect);
+
+        let center = transform.center();
+        let tile_damage = match self.world.tile_at(center).map(|tile| &tile.state) {
+            Some(TileState::Custom(id)) => self.tile_damage.get(id).copied().unwrap_or(0),
+            _ => 0,
+        };
+
+        let deep_water = self.world.is_deep_water(center);
+        let ecs = &mut self.world.ecs;
+        let hp = ecs.healths.get(self.player).map(|health| health.hp);
+        player::damage(ecs, self.player, contact_damage.max(tile_damage));
+        player::update_health(ecs, self.player, dt, deep_water);
+        if ecs.healths.get(self.player).map(|health| health.hp) != hp {
+            self.unsaved_changes = true;
+        }
+    }
+
+    pub fn respawn(&mut self) {
+        if self.is_player_dead() {
+            player::respawn(&mut self.world.ecs, self.player);
+            self.unsaved_changes = true;
+        }
+    }
+
+    /// Whether the tile at `target` is close enough for the player to edit.
+    pub fn in_reach(&self, target: Vec2) -> bool {
+        self.reaches(self.player, target, REACH_DISTANCE)
+    }
+
+    /// Whether the remote `player` is close enough to have made `edit`.
+    /// Blocks can be hit anywhere, so the point of a block closest to the
+    /// player counts.
+    pub fn can_edit(&self, player: Entity, edit: &WorldEdit) -> bool {
+        let mut target = edit.pos();
+        if let (WorldEdit::HitBlock { .. }, Some(center)) = (edit, self.world.ecs.transforms.get(player).map(|transform| transform.center())) {
+            if let Some(rect) = self.world.block_at(target).and_then(|block| self.world.ecs.transforms.get(block)).map(|transform| transform.rect()) {
+                target = center.clamp(rect.point(), rect.point() + rect.size());
+            }
+        }
+        self.reaches(player, target, REACH_DISTANCE + NET_REACH_SLACK)
+    }
+
+    fn reaches(&self, player: Entity, target: Vec2, distance: f32) -> bool {
+        let ecs = &self.world.ecs;
+        if ecs.healths.get(player).is_none_or(|health| health.is_dead()) {
+            return false;
+        }
+        let (Some(transform), Some((tile_x, tile_y))) = (ecs.transforms.get(player), World::tile_coords_at(target)) else {
+            return false;
+        };
+        let tile_center = vec2(tile_x as f32 + 0.5, tile_y as f32 + 0.5) * TILE_SIZE;
+        tile_center.distance(transform.center()) <= distance
+    }
+
+    /// Makes the player walk to `target` around blocks. Returns `false` if
+    /// there is no path.
+    pub fn walk_to(&mut self, target: Vec2) -> bool {
+        if self.is_player_dead() {
+            return false;
+        }
+        match pathfinding::find_path(&self.world, self.player_center(), target, false) {
+            Some(waypoints) => {
+                self.world.ecs.paths.insert(self.player, Path::new(waypoints));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Uses the selected item on the tile at `target`. Returns the edit that
+    /// was made, so it can be sent to the server.
+    pub fn use_item(&mut self, target: Vec2) -> Option<WorldEdit> {
+        if self.player().selected_item() == Some(&Item::Meat) {
+            self.eat();
+            return None;
+        }
+        if !self.in_reach(target) {
+            return None;
+        }
+        let tile = self.world.tile_at(target)?;
+        let pos = Vec2Save::from(tile.pos);
+        let free = self.world.block_at(target).is_none();
+
+        // Placing uses up the item, digging gives the placed tile back.
+        let mut consumed = false;
+        let mut dug_up = None;
+        let edit = match self.player().selected_item()? {
+            Item::Hoe if free && tile.state == TileState::Grass => {
+                if gen_range(0.0, 1.0) < SEED_CHANCE {
+                    dug_up = Some(Item::Seeds);
+                }
+                WorldEdit::Tile { pos, state: TileState::Tilled }
+            }
+            Item::Seeds if free && tile.state == TileState::Tilled => {
+                consumed = true;
+                WorldEdit::PlaceBlock(BlockSave { pos, block_type: BlockType::Crop, growth: 0.0 })
+            }
+            Item::Shovel => {
+                let ground = self.generator.get_tile_state(tile.biome.clone());
+                if tile.state == ground {
+                    return None;
+                }
+                if tile.state != TileState::Tilled {
+                    dug_up = Some(Item::Tile(tile.state.clone()));
+                }
+                WorldEdit::Tile { pos, state: ground }
+            }
+            Item::Tile(state) if free && tile.state != *state => {
+                consumed = true;
+                WorldEdit::Tile { pos, state: state.clone() }
+            }
+            Item::Block(block_type) if free && tile.state != TileState::Water => {
+                let size = block_type.size();
+                let rect = Rect::new(tile.pos.x, tile.pos.y, size.x, size.y);
+
+                let ecs = &self.world.ecs;
+                let blocked = rect.overlaps(&self.player_transform().rect()) ||
+                    self.world.entities_near(tile.pos, REACH_DISTANCE).into_iter()
+                        .filter(|other| ecs.blocks.contains(*other))
+                        .any(|other| ecs.transforms.get(other).is_some_and(|transform| transform.rect().overlaps(&rect)));
+                if blocked {
+                    return None;
+                }
+                consumed = true;
+                WorldEdit::PlaceBlock(BlockSave { pos, block_type: block_type.clone(), growth: 0.0 })
+            }
+            _ => return None,
+        };
+
+        if !self.apply_edit(&edit) {
+            return None;
+        }
+        let player = self.player_mut();
+        if consumed {
+            player.inventory.take_one(player.selected_slot);
+        }
+        if let Some(item) = dug_up {
+            player.inventory.add(item, 1);
+        }
+        Some(edit)
+    }
+
+    fn eat(&mut self) {
+        let ecs = &mut self.world.ecs;
+        let (Some(health), Some(player)) = (ecs.healths.get_mut(self.player), ecs.players.get_mut(self.player)) else {
+            return;
+        };
+        if health.is_dead() || health.hp >= health.max_hp {
+            return;
+        }
+        if player.inventory.take_one(player.selected_slot) {
+            health.heal(MEAT_HEAL);
+            self.unsaved_changes = true;
+        }
+    }
+
+    /// Hits the mob or block at `target`; drops of a killed mob or destroyed
+    /// block go to the player. Returns the edit for the server.
+    pub fn break_block(&mut self, target: Vec2) -> Option<WorldEdit> {
+        if !self.in_reach(target) {
+            return None;
+        }
+        if self.world.mob_at(target).is_some() {
+            if let Some(drops) = self.world.hit_mob(target, 1) {
+                self.give(drops);
+                self.unsaved_changes = true;
+            }
+            return Some(WorldEdit::HitMob { pos: Vec2Save::from(target), damage: 1 });
+        }
+        let entity = self.world.block_at(target)?;
+        let pos = self.world.ecs.transforms.get(entity)?.pos;
+        if let Some(drops) = self.world.hit_block(pos, 1) {
+            self.give(drops);
+        }
+        self.unsaved_changes = true;
+
+        Some(WorldEdit::HitBlock { pos: Vec2Save::from(pos), damage: 1 })
+    }
+
+    fn give(&mut self, stacks: Vec<ItemStack>) {
+        let player = self.player_mut();
+        for stack in stacks {
+            player.inventory.add(stack.item, stack.count);
+        }
+    }
+
+    pub fn craft(&mut self, recipe: usize) -> bool {
+        let Some(player) = self.world.ecs.players.get_mut(self.player) else {
+            return false;
+        };
+        let crafted = self.recipes.craft(recipe, &mut player.inventory);
+        self.unsaved_changes |= crafted;
+        crafted
+    }
+
+    pub fn apply_edit(&mut self, edit: &WorldEdit) -> bool {
+        let changed = self.world.apply_edit(edit);
+        self.unsaved_changes |= changed;
+        changed
+    }
+
+    /// Only edited chunks are saved, the rest is generated again from the seed.
+    pub fn save(&self, filename: &str) -> Result<(), Box<dyn Error>> {
+        let player = self.player();
+        let save = WorldSave {
+            seed: self.generator.seed,
+            player_pos: Vec2Save::from(self.player_transform().pos),
+            inventory: Some(player.inventory.clone()),
+            hp: self.world.ecs.healths.get(self.player).map(|health| health.hp),
+            spawn_point: Some(Vec2Save::from(player.spawn_point)),
+            time: self.world.clock.time,
+            day_length: self.world.clock.day_length,
+            weather: self.world.weather,
+            chunks: self.world.chunks.values()
+                .filter(|chunk| chunk.dirty || !chunk.mobs.is_empty())
+                .map(|chunk| ChunkSave { saved_at: self.world.clock.time, ..ChunkSave::new(chunk, &self.world.ecs) })
+                .chain(self.world.edited_chunks.values().cloned())
+                .collect()
+        };
+        
+        let json = serde_json::to_string(&save)?;
+        fs::write(filename, json)?;
+        Ok(())
+    }
+
+    pub fn load(filename: &str, custom_biomes: Vec<BiomeMod>) -> Result<Self, Box<dyn Error>> {
+        let json = fs::read_to_string(filename)?;
+        let save: WorldSave = serde_json::from_str(&json)?;
+
+        let mut simulation = Self::new(save.seed, custom_biomes);
+        player::teleport(&mut simulation.world.ecs, simulation.player, Vec2::from(save.player_pos));
+        simulation.world.clock = Clock::new(save.time, save.day_length);
+        simulation.world.weather = save.weather;
+        if let (Some(hp), Some(health)) = (save.hp, simulation.world.ecs.healths.get_mut(simulation.player)) {
+            health.hp = hp.min(health.max_hp);
+        }
+        if let Some(spawn_point) = save.spawn_point {
+            simulation.player_mut().spawn_point = Vec2::from(spawn_point);
+        }
+        if let Some(inventory) = save.inventory {
+            simulation.player_mut().inventory = inventory;
+        }
+
+        for chunk_save in save.chunks {
+            simulation.world.edited_chunks.insert(chunk_save.pos, chunk_save);
+        }
+
+        Ok(simulation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::consts::{FIXED_TIMESTEP, RENDER_DISTANCE};
+
+    use super::*;
+
+    /// Headless simulation around the spawn point, flattened to grass with
+    /// no blocks or mobs so steps have predictable results.
+    fn simulation() -> Simulation {
+        let mut simulation = Simulation::new(42, Vec::new());
+        simulation.simulate_mobs = false;
+        simulation.stream_chunks(RENDER_DISTANCE);
+
+        let world = &mut simulation.world;
+        for chunk in world.chunks.values_mut() {
+            for tile in chunk.tiles.iter_mut() {
+                tile.state = TileState::Grass;
+            }
+            for entity in chunk.blocks.drain(..).chain(chunk.mobs.drain(..)) {
+                world.ecs.despawn(entity);
+            }
+        }
+        simulation
+    }
+
+    fn tile_center(tile_x: usize, tile_y: usize) -> Vec2 {
+        vec2(tile_x as f32 + 0.5, tile_y as f32 + 0.5) * TILE_SIZE
+    }
+
+    fn run(simulation: &mut Simulation, seconds: f32, movement: Vec2) {
+        let input = InputSnapshot { movement };
+        for _ in 0..(seconds / FIXED_TIMESTEP) as usize {
+            simulation.step(FIXED_TIMESTEP, &input);
+        }
+    }
+
+    fn select(simulation: &mut Simulation, item: &Item) {
+        let player = simulation.player_mut();
+        player.selected_slot = player.inventory.slots.iter()
+            .position(|slot| slot.as_ref().is_some_and(|stack| stack.item == *item))
+            .expect("the starting inventory has the item");
+    }
+
+    #[test]
+    fn player_moves_with_input() {
+        let mut simulation = simulation();
+        let start = simulation.player_transform().pos;
+
+        run(&mut simulation, 1.0, vec2(1.0, 0.0));
+        let pos = simulation.player_transform().pos;
+        assert!(pos.x - start.x > 100.0, "barely moved: {start} to {pos}");
+        assert_eq!(pos.y, start.y);
+
+        run(&mut simulation, 0.5, Vec2::ZERO);
+        assert_eq!(simulation.player_transform().pos, pos);
+    }
+
+    #[test]
+    fn player_stops_at_a_solid_tile() {
+        let mut simulation = simulation();
+        simulation.world.solid_tiles.insert("rock".to_owned());
+        let (tile_x, tile_y) = World::tile_coords_at(simulation.player_center()).unwrap();
+        let wall_x = tile_x + 3;
+        for y in tile_y - 2..=tile_y + 2 {
+            assert!(simulation.world.set_tile(tile_center(wall_x, y), TileState::Custom("rock".to_owned())));
+        }
+
+        run(&mut simulation, 2.0, vec2(1.0, 0.0));
+        let right = simulation.player_transform().rect().right();
+        let wall = wall_x as f32 * TILE_SIZE;
+        assert!(right <= wall, "walked into the wall: {right}");
+        assert!(wall - right < 0.1, "stopped short of the wall: {right}");
+    }
+
+    #[test]
+    fn placing_and_breaking_show_up_in_the_world() {
+        let mut simulation = simulation();
+        let (tile_x, tile_y) = World::tile_coords_at(simulation.player_center()).unwrap();
+        let target = tile_center(tile_x + 2, tile_y);
+
+        select(&mut simulation, &Item::Tile(TileState::Sand));
+        let sand = simulation.player().inventory.count(&Item::Tile(TileState::Sand));
+        assert!(simulation.use_item(target).is_some());
+        assert!(simulation.world.tile_at(target).is_some_and(|tile| tile.state == TileState::Sand));
+        assert_eq!(simulation.player().inventory.count(&Item::Tile(TileState::Sand)), sand - 1);
+
+        select(&mut simulation, &Item::Block(BlockType::Cactus));
+        assert!(simulation.use_item(target).is_some());
+        assert!(simulation.world.block_at(target).is_some());
+        assert!(simulation.unsaved_changes);
+
+        for _ in 0..10 {
+            if simulation.world.block_at(target).is_none() {
+                break;
+            }
+            assert!(simulation.break_block(target).is_some());
+            run(&mut simulation, 1.0, Vec2::ZERO);
+        }
+        assert!(simulation.world.block_at(target).is_none());
+        assert!(simulation.player().inventory.count(&Item::CactusPiece) > 0);
+    }
+
+    #[test]
+    fn edits_out_of_reach_are_refused() {
+        let mut simulation = simulation();
+        let (tile_x, tile_y) = World::tile_coords_at(simulation.player_center()).unwrap();
+        let target = tile_center(tile_x + 8, tile_y);
+
+        select(&mut simulation, &Item::Tile(TileState::Sand));
+        assert!(simulation.use_item(target).is_none());
+        assert!(simulation.world.tile_at(target).is_some_and(|tile| tile.state == TileState::Grass));
+    }
+
+    #[test]
+    fn remote_edits_must_be_in_reach() {
+        let mut simulation = simulation();
+        let other = simulation.add_other(1, simulation.player_transform().pos);
+        let (tile_x, tile_y) = World::tile_coords_at(simulation.player_center()).unwrap();
+        let edit = |tile_x, tile_y| WorldEdit::Tile { pos: Vec2Save::from(tile_center(tile_x, tile_y)), state: TileState::Sand };
+
+        assert!(simulation.can_edit(other, &edit(tile_x + 3, tile_y)));
+        assert!(!simulation.can_edit(other, &edit(tile_x + 10, tile_y)));
+    }
+}
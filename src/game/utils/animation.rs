@@ -0,0 +1,63 @@
+use macroquad::prelude::*;
+
+/// Time every animated tile and block is drawn at, so they all show the
+/// same frame. Advanced once per rendered frame.
+#[derive(Default)]
+pub struct AnimationClock {
+    pub time: f32,
+}
+
+impl AnimationClock {
+    pub fn advance(&mut self, dt: f32) {
+        self.time += dt;
+    }
+}
+
+/// Frames of equal width side by side in one texture, played in a loop.
+/// A plain texture is a sheet with one frame.
+#[derive(Clone)]
+pub struct SpriteSheet {
+    pub texture: Texture2D,
+    pub frames: u32,
+    /// Seconds each frame is shown.
+    pub frame_time: f32,
+}
+
+impl SpriteSheet {
+    pub fn new(texture: Texture2D, frames: u32, frame_time: f32) -> Self {
+        Self {
+            texture,
+            frames: frames.max(1),
+            frame_time: frame_time.max(0.01),
+        }
+    }
+
+    pub fn single(texture: Texture2D) -> Self {
+        Self::new(texture, 1, 1.0)
+    }
+
+    pub async fn load(path: &str, frames: u32, frame_time: f32) -> Result<Self, macroquad::Error> {
+        Ok(Self::new(load_texture(path).await?, frames, frame_time))
+    }
+
+    /// Part of the texture showing the frame at `time` on the animation clock.
+    pub fn source(&self, time: f32) -> Rect {
+        let frame = (time / self.frame_time) as u32 % self.frames;
+        let width = self.texture.width() / self.frames as f32;
+        Rect::new(frame as f32 * width, 0.0, width, self.texture.height())
+    }
+
+    pub fn draw(&self, pos: Vec2, dest_size: Vec2, time: f32) {
+        draw_texture_ex(
+            &self.texture,
+            pos.x,
+            pos.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(dest_size),
+                source: Some(self.source(time)),
+                ..Default::default()
+            }
+        );
+    }
+}
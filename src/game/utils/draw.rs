@@ -1,7 +1,12 @@
 use macroquad::prelude::*;
 
+use super::animation::SpriteSheet;
+
+/// Texture, position, size, size on screen and part of the texture to draw.
+type BatchItem = (Texture2D, Vec2, f32, Option<Vec2>, Option<Rect>);
+
 pub struct DrawBatch {
-    textures: Vec<(Texture2D, Vec2, f32, Option<Vec2>)>, 
+    textures: Vec<BatchItem>, 
 }
 
 impl DrawBatch {
@@ -12,11 +17,16 @@ impl DrawBatch {
     }
 
     pub fn add(&mut self, texture: Texture2D, pos: Vec2, size: f32, dest_size: Option<Vec2>) {
-        self.textures.push((texture, pos, size, dest_size));
+        self.textures.push((texture, pos, size, dest_size, None));
+    }
+
+    /// Adds the frame of `sheet` shown at `time` on the animation clock.
+    pub fn add_sprite(&mut self, sheet: &SpriteSheet, pos: Vec2, size: f32, dest_size: Option<Vec2>, time: f32) {
+        self.textures.push((sheet.texture.clone(), pos, size, dest_size, Some(sheet.source(time))));
     }
 
     pub fn draw(&mut self) {
-        for (texture, pos, size, dest_size) in &self.textures {
+        for (texture, pos, size, dest_size, source) in &self.textures {
             draw_texture_ex(
                 texture,
                 pos.x,
@@ -24,6 +34,7 @@ impl DrawBatch {
                 WHITE,
                 DrawTextureParams {
                     dest_size: *dest_size,
+                    source: *source,
                     ..Default::default()
                 }
             );
@@ -32,8 +43,14 @@ impl DrawBatch {
     }
 }
 
+/// World-space rectangle seen through `camera`, taking its zoom into account.
+pub fn view_rect(camera: &Camera2D) -> Rect {
+    let half = vec2(1.0 / camera.zoom.x.abs(), 1.0 / camera.zoom.y.abs());
+    Rect::new(camera.target.x - half.x, camera.target.y - half.y, half.x * 2.0, half.y * 2.0)
+}
+
 pub fn distance_squared(a: (usize, usize), b: (usize, usize)) -> f32 {
     let dx = a.0 as f32 - b.0 as f32;
     let dy = a.1 as f32 - b.1 as f32;
     dx * dx + dy * dy
-}
\ No newline at end of file
+}
\ No newline at end of file
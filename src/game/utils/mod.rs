@@ -1 +1,4 @@
-pub mod draw;
\ No newline at end of file
+pub mod animation;
+pub mod draw;
+pub mod particles;
+pub mod render;
\ No newline at end of file
@@ -0,0 +1,132 @@
+use std::{collections::HashMap, error::Error, fs};
+
+use game_core::EmitterMod;
+use macroquad::{prelude::*, rand::gen_range};
+
+/// Something that happened in the world at `pos`, e.g. `step:sand` or
+/// `hit:tree`. Emitters with the same trigger play when it is shown.
+pub struct Effect {
+    pub trigger: String,
+    pub pos: Vec2,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Particle {
+    pos: Vec2,
+    velocity: Vec2,
+    gravity: f32,
+    size: f32,
+    color: Color,
+    age: f32,
+    lifetime: f32,
+}
+
+impl Particle {
+    fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+}
+
+/// Particles in world space, kept in a fixed pool so bursts don't
+/// allocate. Emitters come from `particles.json` and mods.
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    /// Indices of dead particles in `particles`.
+    free: Vec<usize>,
+    /// Emitters by trigger.
+    emitters: HashMap<String, Vec<EmitterMod>>,
+}
+
+impl ParticleSystem {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            particles: vec![Particle::default(); capacity],
+            free: (0..capacity).rev().collect(),
+            emitters: HashMap::new(),
+        }
+    }
+
+    pub fn load(filename: &str, capacity: usize, custom_emitters: &[EmitterMod]) -> Self {
+        let mut system = Self::new(capacity);
+        match Self::read(filename) {
+            Ok(emitters) => system.add_emitters(&emitters),
+            Err(e) => println!("Failed to load particle emitters from {}: {}", filename, e),
+        }
+        system.add_emitters(custom_emitters);
+        system
+    }
+
+    fn read(filename: &str) -> Result<Vec<EmitterMod>, Box<dyn Error>> {
+        let json = fs::read_to_string(filename)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Emitters with the id of an existing one replace it.
+    pub fn add_emitters(&mut self, emitters: &[EmitterMod]) {
+        for emitter in emitters {
+            for list in self.emitters.values_mut() {
+                list.retain(|existing| existing.id != emitter.id);
+            }
+            self.emitters.entry(emitter.trigger.clone()).or_default().push(emitter.clone());
+        }
+    }
+
+    /// Plays every emitter listening to `trigger` at `pos`.
+    pub fn emit(&mut self, trigger: &str, pos: Vec2) {
+        let Some(emitters) = self.emitters.get(trigger) else {
+            return;
+        };
+        for emitter in emitters {
+            for _ in 0..emitter.count {
+                let Some(index) = self.free.pop() else {
+                    return;
+                };
+                let angle = (emitter.direction + gen_range(-0.5, 0.5) * emitter.spread).to_radians();
+                let speed = gen_range(emitter.speed.0, emitter.speed.1.max(emitter.speed.0));
+                self.particles[index] = Particle {
+                    pos,
+                    velocity: vec2(angle.cos(), angle.sin()) * speed,
+                    gravity: emitter.gravity,
+                    size: emitter.size,
+                    color: Color::from(emitter.color),
+                    age: 0.0,
+                    lifetime: emitter.lifetime * gen_range(0.7, 1.0),
+                };
+            }
+        }
+    }
+
+    pub fn emit_all(&mut self, effects: impl IntoIterator<Item = Effect>) {
+        for effect in effects {
+            self.emit(&effect.trigger, effect.pos);
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for (index, particle) in self.particles.iter_mut().enumerate() {
+            if !particle.is_alive() {
+                continue;
+            }
+            particle.age += dt;
+            if !particle.is_alive() {
+                self.free.push(index);
+                continue;
+            }
+            particle.velocity.y += particle.gravity * dt;
+            particle.pos += particle.velocity * dt;
+        }
+    }
+
+    /// Draws the live particles inside `view`.
+    pub fn draw(&self, view: &Rect) {
+        for particle in self.particles.iter().filter(|particle| particle.is_alive() && view.contains(particle.pos)) {
+            let fade = 1.0 - particle.age / particle.lifetime;
+            let color = Color { a: particle.color.a * fade, ..particle.color };
+            draw_rectangle(particle.pos.x - particle.size / 2.0, particle.pos.y - particle.size / 2.0, particle.size, particle.size, color);
+        }
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.particles.len() - self.free.len()
+    }
+}
@@ -0,0 +1,68 @@
+use macroquad::math::Vec2;
+
+use crate::game::{ecs::{components::Sprite, ecs::{Ecs, Entity}}, entity::{block::{block, textures::BlockTextures}, mob::{mob, textures::MobTextures}, player::{player, textures::PlayerTextures}}};
+
+/// Textures of every kind of sprite. The menu only draws blocks, so the
+/// others are optional.
+pub struct SpriteTextures<'a> {
+    pub blocks: &'a BlockTextures,
+    pub mobs: Option<&'a MobTextures>,
+    pub players: Option<&'a PlayerTextures>,
+    /// Time on the animation clock, for animated blocks.
+    pub time: f32,
+}
+
+struct DepthItem {
+    entity: Entity,
+    /// Interpolated position for moving entities.
+    pos: Vec2,
+    depth: f32,
+}
+
+/// Draws entities with a sprite back to front by the y of their base, then
+/// draws canopies of tall blocks on top so they can hide what stands behind.
+#[derive(Default)]
+pub struct DepthQueue {
+    items: Vec<DepthItem>,
+}
+
+impl DepthQueue {
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new()
+        }
+    }
+
+    pub fn add(&mut self, ecs: &Ecs, entity: Entity) {
+        let Some(transform) = ecs.transforms.get(entity) else {
+            return;
+        };
+        let pos = ecs.interpolations.get(entity).map(|interpolation| interpolation.render_pos).unwrap_or(transform.pos);
+        self.items.push(DepthItem { entity, pos, depth: pos.y + transform.size.y });
+    }
+
+    pub fn draw(&mut self, ecs: &Ecs, textures: &SpriteTextures) {
+        self.items.sort_by(|a, b| a.depth.total_cmp(&b.depth));
+
+        for item in &self.items {
+            let (Some(transform), Some(sprite)) = (ecs.transforms.get(item.entity), ecs.sprites.get(item.entity)) else {
+                continue;
+            };
+            if block::draw_block(ecs, item.entity, transform, sprite, textures.blocks, textures.time) {
+                continue;
+            }
+            match (sprite, textures.mobs, textures.players) {
+                (Sprite::Mob(kind), Some(mob_textures), _) => mob::draw(ecs, item.entity, transform, kind, mob_textures),
+                (Sprite::Player, _, Some(player_textures)) => player::draw(ecs, item.entity, item.pos, player_textures),
+                _ => {}
+            }
+        }
+        for item in &self.items {
+            if let (Some(transform), Some(sprite)) = (ecs.transforms.get(item.entity), ecs.sprites.get(item.entity)) {
+                block::draw_canopy(transform, sprite, textures.blocks, textures.time);
+            }
+        }
+        self.items.clear();
+    }
+}
+
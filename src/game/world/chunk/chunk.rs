@@ -1,14 +1,32 @@
-use crate::{game::{entity::block::block::Block, world::tile::tile::Tile}, utils::consts::{CHUNK_PIXELS, CHUNK_SIZE, TILE_SIZE}};
+use crate::{game::{ecs::ecs::Entity, world::tile::tile::Tile}, utils::consts::{CHUNK_PIXELS, CHUNK_SIZE}};
 use macroquad::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub type ChunkPos = (usize, usize);
+
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(0);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
 pub struct Chunk {
     pub tiles: Vec<Tile>,
-    pub blocks: Vec<Box<dyn Block>>,
-    pub pos: (usize, usize),
+    /// Block entities standing in the chunk.
+    pub blocks: Vec<Entity>,
+    /// Mob entities standing in the chunk.
+    pub mobs: Vec<Entity>,
+    pub pos: ChunkPos,
+    /// Edited since it was generated, so it has to be saved.
+    pub dirty: bool,
+    /// Changes whenever the chunk's tiles or blocks do, and is never reused
+    /// by another chunk, so caches can tell when they are out of date.
+    pub revision: u64,
     bounds: (Vec2, Vec2),
 }
 
 impl Chunk {
-    pub fn new(pos: (usize, usize)) -> Self {
+    pub fn new(pos: ChunkPos) -> Self {
         let min = vec2(
             pos.0 as f32 * CHUNK_PIXELS,
             pos.1 as f32 * CHUNK_PIXELS
@@ -20,28 +38,51 @@ impl Chunk {
         Self {
             tiles: Vec::new(),
             blocks: Vec::new(),
+            mobs: Vec::new(),
             pos,
+            dirty: false,
+            revision: next_revision(),
             bounds: (min, max),
         }
     }
 
-    pub fn is_visible(&self, camera_pos: Vec2, screen_size: Vec2) -> bool {
-        let chunk_min = Vec2::new(
-            self.pos.0 as f32 * CHUNK_SIZE as f32 * TILE_SIZE,
-            self.pos.1 as f32 * CHUNK_SIZE as f32 * TILE_SIZE
-        );
-        
-        let chunk_max = chunk_min + Vec2::new(
-            CHUNK_SIZE as f32 * TILE_SIZE,
-            CHUNK_SIZE as f32 * TILE_SIZE
-        );
-        
-        let screen_min = camera_pos - screen_size / 2.0;
-        let screen_max = camera_pos + screen_size / 2.0;
-        
-        !(chunk_max.x < screen_min.x ||
-        chunk_min.x > screen_max.x ||
-        chunk_max.y < screen_min.y ||
-        chunk_min.y > screen_max.y)
+    pub fn set_pos(&mut self, pos: ChunkPos) {
+        let min = vec2(pos.0 as f32 * CHUNK_PIXELS, pos.1 as f32 * CHUNK_PIXELS);
+        self.pos = pos;
+        self.dirty = false;
+        self.revision = next_revision();
+        self.blocks.clear();
+        self.mobs.clear();
+        self.bounds = (min, min + vec2(CHUNK_PIXELS, CHUNK_PIXELS));
+    }
+
+    /// Marks the chunk as edited after its tiles or blocks changed.
+    pub fn changed(&mut self) {
+        self.dirty = true;
+        self.revision = next_revision();
+    }
+
+    /// Tiles are stored row by row, so a local tile is found without searching.
+    pub fn tile_index(local_x: usize, local_y: usize) -> usize {
+        local_y * CHUNK_SIZE + local_x
+    }
+
+    pub fn tile(&self, local_x: usize, local_y: usize) -> Option<&Tile> {
+        if local_x >= CHUNK_SIZE || local_y >= CHUNK_SIZE {
+            return None;
+        }
+        self.tiles.get(Self::tile_index(local_x, local_y))
+    }
+
+    pub fn tile_mut(&mut self, local_x: usize, local_y: usize) -> Option<&mut Tile> {
+        if local_x >= CHUNK_SIZE || local_y >= CHUNK_SIZE {
+            return None;
+        }
+        self.tiles.get_mut(Self::tile_index(local_x, local_y))
     }
-}
\ No newline at end of file
+
+    pub fn is_visible(&self, view: &Rect) -> bool {
+        let (min, max) = self.bounds;
+        Rect::new(min.x, min.y, max.x - min.x, max.y - min.y).overlaps(view)
+    }
+}
@@ -22,6 +22,7 @@ impl ChunkPool {
     pub fn return_chunk(&mut self, mut chunk: Chunk) {
         chunk.tiles.clear();
         chunk.blocks.clear();
+        chunk.mobs.clear();
 
         if self.available.len() < self.max_size {
             self.available.push(chunk);
@@ -37,4 +38,4 @@ impl ChunkPool {
     pub fn is_full(&self) -> bool {
         self.available.len() >= self.max_size
     }
-}
\ No newline at end of file
+}
\ No newline at end of file
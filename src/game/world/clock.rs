@@ -0,0 +1,141 @@
+use crate::utils::consts::{DAY_LENGTH, NIGHT_START, SEASON_DAYS, SNOW_TEMPERATURE};
+
+/// Part of the day when the sun comes up. Days start at sunrise.
+const DAWN_END: f64 = 0.05;
+/// Part of the day when it starts getting dark.
+const DUSK_START: f64 = 0.55;
+/// Part of the day when it is fully dark.
+const DUSK_END: f64 = 0.65;
+/// Part of the day when it starts getting light again.
+const DAWN_START: f64 = 0.95;
+
+/// Game time of a world. Advanced by the simulation and saved with it.
+#[derive(Clone, Copy, Debug)]
+pub struct Clock {
+    /// Seconds of game time since the world was created.
+    pub time: f64,
+    /// Seconds from one sunrise to the next.
+    pub day_length: f64,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new(0.0, DAY_LENGTH)
+    }
+}
+
+impl Clock {
+    pub fn new(time: f64, day_length: f64) -> Self {
+        Self {
+            time,
+            day_length: day_length.max(1.0),
+        }
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        self.time += dt as f64;
+    }
+
+    /// Part of the current day that has passed, from 0 at sunrise to 1.
+    pub fn time_of_day(&self) -> f64 {
+        (self.time / self.day_length).fract()
+    }
+
+    /// Number of the current day, starting at 1.
+    pub fn day(&self) -> u64 {
+        (self.time / self.day_length) as u64 + 1
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.time_of_day() >= NIGHT_START
+    }
+
+    /// Seconds of night between the game times `from` and `to`.
+    pub fn night_between(&self, from: f64, to: f64) -> f64 {
+        (self.night_until(to) - self.night_until(from)).max(0.0)
+    }
+
+    /// Seconds of night from the creation of the world until `time`.
+    fn night_until(&self, time: f64) -> f64 {
+        let days = (time / self.day_length).floor();
+        let into_day = time - days * self.day_length;
+        days * (1.0 - NIGHT_START) * self.day_length + (into_day - NIGHT_START * self.day_length).max(0.0)
+    }
+
+    /// How bright the sun is, from 0 at night to 1 at noon.
+    pub fn daylight(&self) -> f32 {
+        let t = self.time_of_day();
+        let light = if t < DAWN_END {
+            0.5 + 0.5 * t / DAWN_END
+        } else if t < DUSK_START {
+            1.0
+        } else if t < DUSK_END {
+            1.0 - (t - DUSK_START) / (DUSK_END - DUSK_START)
+        } else if t < DAWN_START {
+            0.0
+        } else {
+            0.5 * (t - DAWN_START) / (1.0 - DAWN_START)
+        };
+        light as f32
+    }
+
+    /// Time of day on a 24 hour clock with sunrise at 06:00, like "Day 3 14:30".
+    pub fn display(&self) -> String {
+        let minutes = ((self.time_of_day() * 24.0 + 6.0) % 24.0 * 60.0) as u32;
+        format!("Day {} {:02}:{:02}", self.day(), minutes / 60, minutes % 60)
+    }
+}
+
+/// Seasons last `SEASON_DAYS` days each and shift the temperature of the
+/// whole world, moving the snow line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    /// Added to the generated temperature of every tile.
+    pub fn temperature_offset(&self) -> f64 {
+        match self {
+            Season::Spring | Season::Autumn => 0.0,
+            Season::Summer => 0.1,
+            Season::Winter => -0.15,
+        }
+    }
+
+    /// Generated temperature below which tiles are snowy this season.
+    pub fn snow_line(&self) -> f32 {
+        (SNOW_TEMPERATURE - self.temperature_offset()) as f32
+    }
+}
+
+impl Clock {
+    pub fn season(&self) -> Season {
+        match (self.day() - 1) / SEASON_DAYS % 4 {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_night_between_two_times() {
+        let clock = Clock::new(0.0, 100.0);
+        let night = (1.0 - NIGHT_START) * 100.0;
+
+        assert_eq!(clock.night_between(0.0, NIGHT_START * 100.0), 0.0);
+        assert!((clock.night_between(0.0, 100.0) - night).abs() < 1e-9);
+        assert!((clock.night_between(50.0, 350.0) - night * 3.0).abs() < 1e-9);
+        assert!((clock.night_between(90.0, 110.0) - 10.0).abs() < 1e-9);
+        assert_eq!(clock.night_between(110.0, 90.0), 0.0);
+    }
+}
@@ -0,0 +1,26 @@
+use macroquad::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::save::{block::BlockSave, vec2::Vec2Save};
+
+use super::tile::state::TileState;
+
+/// A change to the world made by a player. Sent to the server and from
+/// there to the other players.
+#[derive(Serialize, Deserialize, Clone)]
+pub enum WorldEdit {
+    Tile { pos: Vec2Save, state: TileState },
+    PlaceBlock(BlockSave),
+    HitBlock { pos: Vec2Save, damage: i32 },
+    HitMob { pos: Vec2Save, damage: i32 },
+}
+
+impl WorldEdit {
+    /// Where in the world the edit was made.
+    pub fn pos(&self) -> Vec2 {
+        match self {
+            WorldEdit::Tile { pos, .. } | WorldEdit::HitBlock { pos, .. } | WorldEdit::HitMob { pos, .. } => Vec2::from(pos.clone()),
+            WorldEdit::PlaceBlock(save) => Vec2::from(save.pos.clone()),
+        }
+    }
+}
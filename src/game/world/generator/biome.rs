@@ -51,3 +51,14 @@ pub enum Biome {
     Desert,
     Custom(String),  
 }
+
+impl Biome {
+    /// Rough temperature of the biome, for tiles whose own is unknown.
+    pub fn typical_temperature(&self) -> f32 {
+        match self {
+            Biome::SnowPlains | Biome::SnowForest => 0.2,
+            Biome::Desert => 0.9,
+            _ => 0.5,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use game_core::{BiomeConditions, BiomeMod};
 use noise::{NoiseFn, Perlin};
 use macroquad::prelude::*;
-use crate::{game::{entity::block::{block::{Block, BlockCache, BlockType}, blocks::{cactus::Cactus, tree::Tree}, textures::BlockTextures}, world::{chunk::chunk::Chunk, tile::{state::TileState, textures::TileTextures, tile::Tile}}}, utils::consts::{BLOCK_CACHE, CHUNK_SIZE}};
+use crate::{game::{ecs::ecs::{Ecs, Entity}, entity::block::blocks::{cactus, tree}, world::{chunk::chunk::Chunk, tile::{state::TileState, tile::Tile}}}, utils::consts::{CHUNK_SIZE, SNOW_TEMPERATURE}};
 
 use super::{biome::{Biome, BiomeCache}, noise::{NoiseCache, NoiseType}};
 
@@ -16,7 +16,6 @@ pub struct WorldGenerator {
         
     noise_cache: NoiseCache,
     biome_cache: BiomeCache,
-    block_cache: BlockCache,
     
     octaves: usize,
     persistence: f64, 
@@ -36,20 +35,20 @@ impl WorldGenerator {
         noise_cache: NoiseCache::new(),
         
         biome_cache: BiomeCache::new(),
-        block_cache: BlockCache::new(BLOCK_CACHE),
         
         octaves: 2,
         persistence: 0.3,  
         lacunarity: 2.0,
         }
     }
-    pub fn generate_chunk_data(&mut self, chunk: &mut Chunk, tile_textures: TileTextures, block_textures: &BlockTextures) {
+    /// Regenerates a pooled chunk, spawning its blocks into `ecs`.
+    pub fn generate_chunk_data(&mut self, chunk: &mut Chunk, ecs: &mut Ecs) {
         let height_map = self.generate_chunk_height_map(chunk.pos.0, chunk.pos.1);
         chunk.tiles.clear();
         chunk.blocks.clear();
 
-        chunk.tiles = self.generate_chunk_tiles(chunk.pos.0, chunk.pos.1, &height_map, tile_textures);
-        chunk.blocks = self.generate_chunk_blocks(chunk.pos.0, chunk.pos.1, &height_map, block_textures);
+        chunk.tiles = self.generate_chunk_tiles(chunk.pos.0, chunk.pos.1, &height_map);
+        chunk.blocks = self.generate_chunk_blocks(chunk.pos.0, chunk.pos.1, &height_map, ecs);
     }
        
     pub fn generate_chunk_height_map(&mut self, chunk_x: usize, chunk_y: usize) -> Vec<Vec<(f64, f64, f64)>> {
@@ -100,17 +99,17 @@ impl WorldGenerator {
         noise_value
     }
     
-    pub async fn generate_chunk(&mut self, chunk_x: usize, chunk_y: usize, tile_textures: TileTextures, block_textures: &BlockTextures) -> Chunk {
+    pub fn generate_chunk(&mut self, chunk_x: usize, chunk_y: usize, ecs: &mut Ecs) -> Chunk {
         let height_map = self.generate_chunk_height_map(chunk_x, chunk_y);
-        let tiles = self.generate_chunk_tiles(chunk_x, chunk_y, &height_map, tile_textures);
-        let blocks = self.generate_chunk_blocks(chunk_x, chunk_y, &height_map, block_textures);
+        let tiles = self.generate_chunk_tiles(chunk_x, chunk_y, &height_map);
+        let blocks = self.generate_chunk_blocks(chunk_x, chunk_y, &height_map, ecs);
         
         let mut chunk = Chunk::new((chunk_x, chunk_y));
         chunk.tiles = tiles;
         chunk.blocks = blocks;
         chunk
     }
-    fn generate_chunk_tiles(&mut self, chunk_x: usize, chunk_y: usize, height_map: &[Vec<(f64, f64, f64)>], tile_textures: TileTextures) -> Vec<Tile> {
+    fn generate_chunk_tiles(&mut self, chunk_x: usize, chunk_y: usize, height_map: &[Vec<(f64, f64, f64)>]) -> Vec<Tile> {
         let mut tiles = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE);
         
         for y in 0..CHUNK_SIZE {
@@ -139,18 +138,18 @@ impl WorldGenerator {
                 
                 let mut tile = Tile::new(
                     world_x as f32 * 32.0,
-                    world_y as f32 * 32.0,
-                    Some(tile_textures.clone())
+                    world_y as f32 * 32.0
                 );
                 tile.state = state;
                 tile.biome = biome;
+                tile.temperature = temperature as f32;
                 tiles.push(tile);
             }
         }
         tiles
     }
-    fn generate_chunk_blocks(&mut self, chunk_x: usize, chunk_y: usize, height_map: &[Vec<(f64, f64, f64)>], textures: &BlockTextures) -> Vec<Box<dyn Block>> {
-        let mut blocks: Vec<Box<dyn Block>> = Vec::new();
+    fn generate_chunk_blocks(&mut self, chunk_x: usize, chunk_y: usize, height_map: &[Vec<(f64, f64, f64)>], ecs: &mut Ecs) -> Vec<Entity> {
+        let mut blocks = Vec::new();
         
         for y in 0..CHUNK_SIZE {
             for x in 0..CHUNK_SIZE {
@@ -171,28 +170,10 @@ impl WorldGenerator {
                 
                 match biome {
                     Biome::Forest | Biome::SnowForest if block_chance > 0.70 => {
-                        if let Some(mut block) = self.block_cache.get_block(BlockType::Tree) {
-                            block.set_position(pos);
-                            block.set_texture(if matches!(biome, Biome::SnowForest) {
-                                textures.tree_snow_top.clone()
-                            } else {
-                                textures.tree_top.clone()
-                            }); 
-                            blocks.push(block);
-                        } else {
-                            blocks.push(Box::new(Tree::new(pos, textures, biome)));
-                        }
+                        blocks.push(tree::spawn(ecs, pos, biome));
                     },
                     Biome::Desert if block_chance > 0.8 => {
-                        if let Some(mut block) = self.block_cache.get_block(BlockType::Cactus) {
-                            block.set_position(pos);
-                            block.set_texture(textures.cactus.clone());
-                            blocks.push(block);
-                        } else {
-                            let mut cactus = Cactus::new(pos);
-                            cactus.set_texture(textures.cactus.clone());
-                            blocks.push(Box::new(cactus));
-                        }
+                        blocks.push(cactus::spawn(ecs, pos));
                     },
                     _ => {}
                 }
@@ -208,6 +189,13 @@ impl WorldGenerator {
             && conditions.temperature_range.map_or(true, |(min, max)| temperature >= min && temperature <= max)
     }
 
+    /// Moisture and temperature of a tile, from 0 to 1, as used to pick its biome.
+    pub fn climate_at(&self, tile_x: usize, tile_y: usize) -> (f64, f64) {
+        let moisture = self.generate_noise_with_octaves(tile_x as f64, tile_y as f64, NoiseType::Moisture);
+        let temperature = self.generate_noise_with_octaves(tile_x as f64, tile_y as f64, NoiseType::Temperature);
+        ((moisture + 1.0) / 2.0, (temperature + 1.0) / 2.0)
+    }
+
     pub fn get_biome(&self, height: f64, moisture: f64, temperature: f64) -> Biome {
         for biome_mod in &self.custom_biomes {
             if self.matches_conditions(&(height, moisture, temperature), &biome_mod.conditions) {
@@ -220,7 +208,7 @@ impl WorldGenerator {
             return Biome::Beach;
         }
 
-        if temperature < 0.3 {
+        if temperature < SNOW_TEMPERATURE {
             if moisture > 0.6 {
                 return Biome::SnowForest;
             } else {
@@ -1 +1 @@
-pub mod world;pub mod chunk; pub mod generator; pub mod tile;
\ No newline at end of file
+pub mod world;pub mod chunk; pub mod clock; pub mod edit; pub mod generator; pub mod tile; pub mod weather;
\ No newline at end of file
@@ -8,4 +8,27 @@ pub enum TileState {
     SnowGrass,
     Water,
     Custom(String),
-}
\ No newline at end of file
+}
+
+impl TileState {
+    /// Id used by recipes and particle emitters, e.g. `sand` or a mod tile's id.
+    pub fn id(&self) -> String {
+        match self {
+            TileState::Grass => "grass".to_owned(),
+            TileState::Tilled => "tilled".to_owned(),
+            TileState::Sand => "sand".to_owned(),
+            TileState::SnowGrass => "snow_grass".to_owned(),
+            TileState::Water => "water".to_owned(),
+            TileState::Custom(id) => id.clone(),
+        }
+    }
+
+    /// Multiplier of the speed of anything walking on the tile.
+    pub fn speed(&self) -> f32 {
+        match self {
+            TileState::Water => 0.5,
+            TileState::Sand | TileState::SnowGrass => 0.9,
+            _ => 1.0,
+        }
+    }
+}
\ No newline at end of file
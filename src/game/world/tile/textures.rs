@@ -2,14 +2,16 @@ use std::collections::HashMap;
 
 use macroquad::texture::Texture2D;
 
+use crate::game::utils::animation::SpriteSheet;
+
 
 #[derive(Clone)]
 pub struct TileTextures {
-    pub grass: Texture2D,
-    pub tilled: Texture2D,
+    pub grass: SpriteSheet,
+    pub tilled: SpriteSheet,
     pub grass_border: Texture2D,
-    pub sand: Texture2D,
-    pub snow_grass: Texture2D,
-    pub water: Texture2D,
-    pub custom: HashMap<String, Texture2D>,
-}
\ No newline at end of file
+    pub sand: SpriteSheet,
+    pub snow_grass: SpriteSheet,
+    pub water: SpriteSheet,
+    pub custom: HashMap<String, SpriteSheet>,
+}
\ No newline at end of file
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use macroquad::{math::{vec2, Vec2}, texture::load_texture};
 
-use crate::game::{utils::draw::DrawBatch, world::generator::biome::Biome};
+use crate::{game::{utils::{animation::SpriteSheet, draw::DrawBatch}, world::generator::biome::Biome}, utils::consts::{WATER_FRAMES, WATER_FRAME_TIME}};
 
 use super::{state::TileState, textures::TileTextures};
 
@@ -11,17 +11,18 @@ pub struct Tile {
     pub pos: Vec2,
     pub biome: Biome,
     pub size: f32,
-    pub textures: Option<TileTextures>,
+    /// Generated temperature, from 0 to 1, before seasons.
+    pub temperature: f32,
 }
 
 impl Tile {
-    pub fn new(x: f32, y: f32, textures: Option<TileTextures>) -> Self {
+    pub fn new(x: f32, y: f32) -> Self {
         Tile {
             state: TileState::Grass,
             biome: Biome::Plains,
             pos: vec2(x, y),
             size: 32.0,
-            textures,
+            temperature: 0.5,
         }
     }
 
@@ -30,51 +31,56 @@ impl Tile {
         let custom = HashMap::new();
         TileTextures {
             
-            grass: load_texture("assets/textures/tiles/grass.png").await.unwrap(),
-            tilled: load_texture("assets/textures/tiles/tilled.png").await.unwrap(),
+            grass: SpriteSheet::load("assets/textures/tiles/grass.png", 1, 1.0).await.unwrap(),
+            tilled: SpriteSheet::load("assets/textures/tiles/tilled.png", 1, 1.0).await.unwrap(),
             grass_border: load_texture("assets/textures/tiles/grass_border.png").await.unwrap(),
-            sand: load_texture("assets/textures/tiles/sand.png").await.unwrap(),
-            snow_grass: load_texture("assets/textures/tiles/snow_grass.png").await.unwrap(),
-            water: load_texture("assets/textures/tiles/water.png").await.unwrap(),
+            sand: SpriteSheet::load("assets/textures/tiles/sand.png", 1, 1.0).await.unwrap(),
+            snow_grass: SpriteSheet::load("assets/textures/tiles/snow_grass.png", 1, 1.0).await.unwrap(),
+            water: SpriteSheet::load("assets/textures/tiles/water.png", WATER_FRAMES, WATER_FRAME_TIME).await.unwrap(),
             custom,
         }
     }
 
-    pub fn draw(&self, tiles: &[Tile], width: usize, batch: &mut DrawBatch) {
-        if let Some(textures) = &self.textures {
-            let texture = match &self.state {
-                TileState::Grass => &textures.grass,
-                TileState::Tilled => &textures.tilled,
-                TileState::Sand => &textures.sand,
-                TileState::SnowGrass => &textures.snow_grass,
-                TileState::Water => &textures.water,
-                TileState::Custom(id) => {
-                    let clean_id = id.trim_start_matches("Custom(").trim_end_matches(")");
-                    if let Some(tex) = textures.custom.get(clean_id) {
-                        tex
-                    } else {
-                        &textures.grass
-                    }
-                },
-            };
-            
-            batch.add(texture.clone(), 
-            vec2(self.pos.x, self.pos.y), 
-            self.size,
-            Some(vec2(self.size, self.size)));
-            
-            let x = (self.pos.x / self.size) as usize;
-            let y = (self.pos.y / self.size) as usize;
-            let tile_index = y * width + x;
-            
-            if y > 0 && tile_index < tiles.len() && tile_index >= width {
-                if tiles[tile_index - width].state == TileState::Tilled && self.state == TileState::Grass {
-                    batch.add(textures.grass_border.clone(),
-                    vec2(self.pos.x, self.pos.y),
-                    self.size,
-                    Some(vec2(self.size, 4.0)));
+    /// Grass colder than `snow_line` is drawn snowy and snow grass warmer
+    /// than it is drawn green, so the snow line moves with the seasons.
+    /// Animated tiles show their frame at `time` on the animation clock.
+    pub fn draw(&self, textures: &TileTextures, tiles: &[Tile], width: usize, snow_line: f32, time: f32, batch: &mut DrawBatch) {
+        let snowy = self.temperature < snow_line;
+        let texture = match &self.state {
+            TileState::Grass if snowy => &textures.snow_grass,
+            TileState::SnowGrass if !snowy => &textures.grass,
+            TileState::Grass => &textures.grass,
+            TileState::Tilled => &textures.tilled,
+            TileState::Sand => &textures.sand,
+            TileState::SnowGrass => &textures.snow_grass,
+            TileState::Water => &textures.water,
+            TileState::Custom(id) => {
+                let clean_id = id.trim_start_matches("Custom(").trim_end_matches(")");
+                if let Some(tex) = textures.custom.get(clean_id) {
+                    tex
+                } else {
+                    &textures.grass
                 }
+            },
+        };
+        
+        batch.add_sprite(texture, 
+        vec2(self.pos.x, self.pos.y), 
+        self.size,
+        Some(vec2(self.size, self.size)),
+        time);
+        
+        let x = (self.pos.x / self.size) as usize % width;
+        let y = (self.pos.y / self.size) as usize % width;
+        let tile_index = y * width + x;
+        
+        if y > 0 && tile_index < tiles.len() && tile_index >= width {
+            if tiles[tile_index - width].state == TileState::Tilled && self.state == TileState::Grass {
+                batch.add(textures.grass_border.clone(),
+                vec2(self.pos.x, self.pos.y),
+                self.size,
+                Some(vec2(self.size, 4.0)));
             }
         }
     }
-}
\ No newline at end of file
+}
\ No newline at end of file
@@ -0,0 +1,62 @@
+use macroquad::rand::gen_range;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::consts::{RAIN_MOISTURE, SNOW_TEMPERATURE, STORM_CHANCE, WEATHER_FADE, WEATHER_MAX_TIME, WEATHER_MIN_TIME};
+
+use super::generator::biome::Biome;
+
+/// What falls from the sky at one place while the weather is bad.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precipitation {
+    Rain,
+    Snow,
+    Sandstorm,
+}
+
+impl Precipitation {
+    /// `temperature` already includes the season's offset.
+    pub fn at(biome: &Biome, moisture: f64, temperature: f64) -> Option<Self> {
+        if *biome == Biome::Desert {
+            Some(Precipitation::Sandstorm)
+        } else if temperature < SNOW_TEMPERATURE {
+            Some(Precipitation::Snow)
+        } else if moisture > RAIN_MOISTURE {
+            Some(Precipitation::Rain)
+        } else {
+            None
+        }
+    }
+}
+
+/// Weather of the whole world. Whether bad weather shows up as rain, snow
+/// or a sandstorm depends on the climate where it is seen.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct Weather {
+    pub stormy: bool,
+    /// Fades towards 1 while stormy and towards 0 otherwise.
+    pub intensity: f32,
+    /// Seconds until the weather changes.
+    pub timer: f32,
+}
+
+impl Weather {
+    pub fn update(&mut self, dt: f32) {
+        self.timer -= dt;
+        if self.timer <= 0.0 {
+            self.stormy = gen_range(0.0, 1.0) < STORM_CHANCE;
+            self.timer = gen_range(WEATHER_MIN_TIME, WEATHER_MAX_TIME);
+        }
+
+        let target = if self.stormy { 1.0 } else { 0.0 };
+        let step = WEATHER_FADE * dt;
+        self.intensity = if self.intensity < target {
+            (self.intensity + step).min(target)
+        } else {
+            (self.intensity - step).max(target)
+        };
+    }
+
+    pub fn is_clear(&self) -> bool {
+        self.intensity <= 0.0
+    }
+}
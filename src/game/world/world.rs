@@ -1,12 +1,33 @@
-use crate::{game::{entity::block::textures::BlockTextures, utils::draw::distance_squared}, utils::consts::{CHUNK_PIXELS, CHUNK_SIZE, MAX_CHUNKS, RENDER_DISTANCE}};
+use std::collections::{HashMap, HashSet};
 
-use super::{chunk::{chunk::Chunk, pool::ChunkPool}, generator::generator::WorldGenerator, tile::textures::TileTextures};
+use game_core::TileMod;
+
+use crate::{game::{ecs::{ecs::{Ecs, Entity}, systems}, entity::mob::{mob, registry::MobRegistry}, item::inventory::ItemStack, utils::{draw::distance_squared, particles::Effect}}, save::{block::BlockSave, chunk::ChunkSave, mob::MobSave}, utils::consts::{CHUNK_PIXELS, CHUNK_SIZE, CROP_NIGHT_GROWTH, CROP_WATER_BONUS, CROP_WATER_RADIUS, MAX_CHUNKS, MAX_LIGHT, MOB_CHASE_DISTANCE, TILE_SIZE}};
+
+use super::{chunk::{chunk::{Chunk, ChunkPos}, pool::ChunkPool}, clock::Clock, edit::WorldEdit, generator::{biome::Biome, generator::WorldGenerator}, tile::{state::TileState, tile::Tile}, weather::Weather};
 use macroquad::prelude::*;
 pub struct World {
-    pub chunks: Vec<Chunk>,
+    pub chunks: HashMap<ChunkPos, Chunk>,
     pub width: usize,
     pub height: usize,
     pub chunk_pool: ChunkPool,
+    /// Edited chunks that are not loaded right now. They are restored
+    /// instead of generated when loaded again.
+    pub edited_chunks: HashMap<ChunkPos, ChunkSave>,
+    pub clock: Clock,
+    pub weather: Weather,
+    /// Blocks, mobs and players. Chunks keep the blocks and mobs standing
+    /// in them, so they are saved and unloaded together.
+    pub ecs: Ecs,
+    pub mob_types: MobRegistry,
+    /// Ids of mod tiles nothing can walk through. They also block light.
+    pub solid_tiles: HashSet<String>,
+    /// Light emitted by mod tiles, by id.
+    pub light_tiles: HashMap<String, u8>,
+    /// Footsteps and hits for the particle system, taken by whoever draws
+    /// the world. Only collected while `record_effects` is set.
+    pub effects: Vec<Effect>,
+    pub record_effects: bool,
 }
 
 impl World {
@@ -19,71 +40,530 @@ impl World {
         width, height, chunks_x, chunks_y);
 
         Self {
-            chunks: Vec::new(),
+            chunks: HashMap::new(),
             width,
             height,
             chunk_pool: ChunkPool::new(pool_size),
+            edited_chunks: HashMap::new(),
+            clock: Clock::default(),
+            weather: Weather::default(),
+            ecs: Ecs::default(),
+            mob_types: MobRegistry::default(),
+            solid_tiles: HashSet::new(),
+            light_tiles: HashMap::new(),
+            effects: Vec::new(),
+            record_effects: false,
         }
     }
 
-    pub fn cleanup_chunks(&mut self) {
-
-        if self.chunks.len() > MAX_CHUNKS {
-            self.chunks.sort_by(|a, b| {
-                let center_x = self.width / 2;
-                let center_y = self.height / 2;
+    /// `centers` is how many players chunks are being kept loaded around.
+    pub fn cleanup_chunks(&mut self, render_distance: f32, centers: usize) {
+        let side = render_distance.ceil() as usize * 2 + 1;
+        let max_chunks = MAX_CHUNKS.max(side * side * centers.max(1));
 
-                let a_dist = distance_squared(a.pos, (center_x, center_y));
-                let b_dist = distance_squared(b.pos, (center_x, center_y));
+        if self.chunks.len() > max_chunks {
+            let center = (self.width / CHUNK_SIZE / 2, self.height / CHUNK_SIZE / 2);
+            let mut positions: Vec<ChunkPos> = self.chunks.keys().copied().collect();
+            positions.sort_by(|a, b| {
+                let a_dist = distance_squared(*a, center);
+                let b_dist = distance_squared(*b, center);
 
                 b_dist.partial_cmp(&a_dist).unwrap()
             });
-            while self.chunks.len() > MAX_CHUNKS {
-                if let Some(chunk) = self.chunks.pop() {
-                    self.chunk_pool.return_chunk(chunk);
+            for pos in positions.into_iter().take(self.chunks.len() - max_chunks) {
+                self.unload_chunk(pos);
+            }
+        }
+    }
+
+    fn unload_chunk(&mut self, pos: ChunkPos) {
+        if let Some(chunk) = self.chunks.remove(&pos) {
+            if chunk.dirty || !chunk.mobs.is_empty() {
+                let mut save = ChunkSave::new(&chunk, &self.ecs);
+                save.saved_at = self.clock.time;
+                self.edited_chunks.insert(pos, save);
+            }
+            self.despawn_chunk(chunk);
+        }
+    }
+
+    fn despawn_chunk(&mut self, chunk: Chunk) {
+        for entity in chunk.blocks.iter().chain(&chunk.mobs) {
+            self.ecs.despawn(*entity);
+        }
+        self.chunk_pool.return_chunk(chunk);
+    }
+
+    /// Adds a chunk received from elsewhere, replacing any local edits of it.
+    pub fn insert_chunk(&mut self, save: ChunkSave) {
+        let pos = save.pos;
+        self.edited_chunks.remove(&pos);
+        if let Some(chunk) = self.chunks.remove(&pos) {
+            self.despawn_chunk(chunk);
+        }
+        let chunk = save.restore(&mut self.ecs, &self.mob_types);
+        self.chunks.insert(pos, chunk);
+    }
+
+    pub fn chunk_pos_at(world_pos: Vec2) -> Option<ChunkPos> {
+        let (tile_x, tile_y) = Self::tile_coords_at(world_pos)?;
+        Some((tile_x / CHUNK_SIZE, tile_y / CHUNK_SIZE))
+    }
+
+    pub fn tile_coords_at(world_pos: Vec2) -> Option<(usize, usize)> {
+        if world_pos.x < 0.0 || world_pos.y < 0.0 {
+            return None;
+        }
+        Some(((world_pos.x / TILE_SIZE) as usize, (world_pos.y / TILE_SIZE) as usize))
+    }
+
+    pub fn tile_at(&self, world_pos: Vec2) -> Option<&Tile> {
+        let (tile_x, tile_y) = Self::tile_coords_at(world_pos)?;
+        self.chunks.get(&(tile_x / CHUNK_SIZE, tile_y / CHUNK_SIZE))?
+            .tile(tile_x % CHUNK_SIZE, tile_y % CHUNK_SIZE)
+    }
+
+    pub fn tile_at_mut(&mut self, world_pos: Vec2) -> Option<&mut Tile> {
+        let (tile_x, tile_y) = Self::tile_coords_at(world_pos)?;
+        self.chunks.get_mut(&(tile_x / CHUNK_SIZE, tile_y / CHUNK_SIZE))?
+            .tile_mut(tile_x % CHUNK_SIZE, tile_y % CHUNK_SIZE)
+    }
+
+    /// Returns `false` if the tile's chunk is not loaded.
+    pub fn set_tile(&mut self, world_pos: Vec2, state: TileState) -> bool {
+        let Some((tile_x, tile_y)) = Self::tile_coords_at(world_pos) else {
+            return false;
+        };
+        let Some(chunk) = self.chunks.get_mut(&(tile_x / CHUNK_SIZE, tile_y / CHUNK_SIZE)) else {
+            return false;
+        };
+        match chunk.tile_mut(tile_x % CHUNK_SIZE, tile_y % CHUNK_SIZE) {
+            Some(tile) => {
+                tile.state = state;
+                chunk.changed();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Chunks a block covering `world_pos` can belong to. Blocks are stored
+    /// in the chunk of their top left corner but may reach into the next one.
+    fn chunks_around(world_pos: Vec2) -> Vec<ChunkPos> {
+        let Some((chunk_x, chunk_y)) = Self::chunk_pos_at(world_pos) else {
+            return Vec::new();
+        };
+        let mut positions = vec![(chunk_x, chunk_y)];
+        for (dx, dy) in [(1, 0), (0, 1), (1, 1)] {
+            if chunk_x >= dx && chunk_y >= dy {
+                positions.push((chunk_x - dx, chunk_y - dy));
+            }
+        }
+        positions
+    }
+
+    pub fn block_at(&self, world_pos: Vec2) -> Option<Entity> {
+        Self::chunks_around(world_pos).into_iter()
+            .filter_map(|pos| self.chunks.get(&pos))
+            .flat_map(|chunk| chunk.blocks.iter())
+            .copied()
+            .find(|entity| self.ecs.transforms.get(*entity).is_some_and(|transform| transform.rect().contains(world_pos)))
+    }
+
+    /// Returns `false` if the block's chunk is not loaded.
+    pub fn place_block(&mut self, save: BlockSave) -> bool {
+        let pos = Vec2::from(save.pos.clone());
+        let Some(chunk_pos) = Self::chunk_pos_at(pos).filter(|chunk_pos| self.chunks.contains_key(chunk_pos)) else {
+            return false;
+        };
+        let biome = self.tile_at(pos).map(|tile| tile.biome.clone()).unwrap_or(Biome::Plains);
+        let entity = save.spawn(&mut self.ecs, biome);
+        let chunk = self.chunks.get_mut(&chunk_pos).unwrap();
+        chunk.blocks.push(entity);
+        chunk.changed();
+        true
+    }
+
+    /// Damages the block at `world_pos`. Returns its drops once it is
+    /// destroyed and removed from the world.
+    pub fn hit_block(&mut self, world_pos: Vec2, damage: i32) -> Option<Vec<ItemStack>> {
+        let entity = self.block_at(world_pos)?;
+        if let (Some(block_type), Some(transform)) = (self.ecs.blocks.get(entity), self.ecs.transforms.get(entity)) {
+            let trigger = format!("hit:{}", block_type.id());
+            let center = transform.center();
+            self.emit(trigger, center);
+        }
+        let drops = self.damage_entity(entity, damage)?;
+        for chunk in self.chunks.values_mut() {
+            if let Some(index) = chunk.blocks.iter().position(|block| *block == entity) {
+                chunk.blocks.remove(index);
+                chunk.changed();
+            }
+        }
+        Some(drops)
+    }
+
+    /// Damages a block or mob and despawns it once its health runs out,
+    /// returning what it drops. Mobs that survive run away.
+    fn damage_entity(&mut self, entity: Entity, damage: i32) -> Option<Vec<ItemStack>> {
+        let health = self.ecs.healths.get_mut(entity)?;
+        if !health.damage(damage, systems::HURT_TIME) {
+            if let Some(ai) = self.ecs.ais.get_mut(entity) {
+                ai.scare();
+            }
+            return None;
+        }
+        let drops = systems::drops(&self.ecs, entity);
+        self.ecs.despawn(entity);
+        Some(drops)
+    }
+
+    /// Returns `false` if nothing changed.
+    pub fn apply_edit(&mut self, edit: &WorldEdit) -> bool {
+        match edit {
+            WorldEdit::Tile { pos, state } => self.set_tile(Vec2::from(pos.clone()), state.clone()),
+            WorldEdit::PlaceBlock(save) => {
+                self.place_block(save.clone())
+            }
+            WorldEdit::HitMob { pos, damage } => {
+                let pos = Vec2::from(pos.clone());
+                if self.mob_at(pos).is_none() {
+                    return false;
+                }
+                self.hit_mob(pos, *damage);
+                true
+            }
+            WorldEdit::HitBlock { pos, damage } => {
+                let pos = Vec2::from(pos.clone());
+                if self.block_at(pos).is_none() {
+                    return false;
+                }
+                self.hit_block(pos, *damage);
+                true
+            }
+        }
+    }
+
+    pub fn emit(&mut self, trigger: String, pos: Vec2) {
+        if self.record_effects {
+            self.effects.push(Effect { trigger, pos });
+        }
+    }
+
+    /// Emits `step:<tile>` for something walking at `feet`.
+    pub fn footstep(&mut self, feet: Vec2) {
+        if let Some(id) = self.tile_at(feet).map(|tile| tile.state.id()) {
+            self.emit(format!("step:{}", id), feet);
+        }
+    }
+
+    /// Water tile that is surrounded by water on all sides.
+    pub fn is_deep_water(&self, world_pos: Vec2) -> bool {
+        [vec2(0.0, 0.0), vec2(-1.0, 0.0), vec2(1.0, 0.0), vec2(0.0, -1.0), vec2(0.0, 1.0)].iter()
+            .all(|offset| self.tile_at(world_pos + *offset * TILE_SIZE).is_some_and(|tile| tile.state == TileState::Water))
+    }
+
+    /// Whether there is water within `CROP_WATER_RADIUS` tiles of `world_pos`.
+    pub fn is_near_water(&self, world_pos: Vec2) -> bool {
+        for dy in -CROP_WATER_RADIUS..=CROP_WATER_RADIUS {
+            for dx in -CROP_WATER_RADIUS..=CROP_WATER_RADIUS {
+                let pos = world_pos + vec2(dx as f32, dy as f32) * TILE_SIZE;
+                if self.tile_at(pos).is_some_and(|tile| tile.state == TileState::Water) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Grows the blocks of every loaded chunk by `seconds`, slower at night.
+    pub fn grow_blocks(&mut self, seconds: f32) {
+        let seconds = if self.is_night() { seconds * CROP_NIGHT_GROWTH } else { seconds };
+        let positions: Vec<ChunkPos> = self.chunks.keys().copied().collect();
+        for pos in positions {
+            self.grow_chunk(pos, seconds);
+        }
+    }
+
+    /// Seconds crops grew since the game time `since`, counting night the
+    /// same way `grow_blocks` does, to catch up chunks that were unloaded.
+    fn growth_since(&self, since: f64) -> f32 {
+        let elapsed = (self.clock.time - since).max(0.0);
+        let night = self.clock.night_between(since, self.clock.time);
+        (elapsed - night + night * CROP_NIGHT_GROWTH as f64) as f32
+    }
+
+    fn grow_chunk(&mut self, pos: ChunkPos, seconds: f32) {
+        let Some(chunk) = self.chunks.get(&pos) else {
+            return;
+        };
+        let growing: Vec<(Entity, bool)> = chunk.blocks.iter()
+            .filter(|entity| self.ecs.growths.contains(**entity))
+            .filter_map(|entity| self.ecs.transforms.get(*entity).map(|transform| (*entity, self.is_near_water(transform.pos))))
+            .collect();
+
+        for (entity, watered) in growing {
+            let growth = self.ecs.growths.get_mut(entity).unwrap();
+            if growth.grow(if watered { seconds * CROP_WATER_BONUS } else { seconds }) {
+                self.chunks.get_mut(&pos).unwrap().dirty = true;
+            }
+        }
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.clock.is_night()
+    }
+
+    pub fn mob_count(&self) -> usize {
+        self.chunks.values().map(|chunk| chunk.mobs.len()).sum()
+    }
+
+    pub fn mobs(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.chunks.values().flat_map(|chunk| chunk.mobs.iter().copied())
+    }
+
+    /// Returns `false` if the chunk at `pos` is not loaded or `kind` is not
+    /// a known mob type.
+    pub fn spawn_mob(&mut self, kind: &str, pos: Vec2) -> bool {
+        let Some(chunk_pos) = Self::chunk_pos_at(pos).filter(|chunk_pos| self.chunks.contains_key(chunk_pos)) else {
+            return false;
+        };
+        let Some(mob_type) = self.mob_types.get(kind) else {
+            return false;
+        };
+        let entity = mob::spawn(&mut self.ecs, mob_type, pos);
+        self.chunks.get_mut(&chunk_pos).unwrap().mobs.push(entity);
+        true
+    }
+
+    /// Spawns a saved mob into the chunk it stands in. Returns `None` if
+    /// that chunk is not loaded or the mob's type is unknown.
+    pub fn add_mob(&mut self, save: MobSave) -> Option<Entity> {
+        let chunk_pos = Self::chunk_pos_at(Vec2::from(save.pos.clone())).filter(|pos| self.chunks.contains_key(pos))?;
+        let entity = save.spawn(&mut self.ecs, &self.mob_types)?;
+        self.chunks.get_mut(&chunk_pos).unwrap().mobs.push(entity);
+        Some(entity)
+    }
+
+    /// Despawns every loaded mob for which `keep` returns `false`.
+    pub fn retain_mobs(&mut self, keep: impl Fn(Entity) -> bool) {
+        for chunk in self.chunks.values_mut() {
+            for entity in chunk.mobs.iter().copied().filter(|entity| !keep(*entity)) {
+                self.ecs.despawn(entity);
+            }
+            chunk.mobs.retain(|entity| keep(*entity));
+        }
+    }
+
+    /// Puts a mob moved elsewhere, e.g. by the server, at `pos` and sorts it
+    /// into the chunk it walked into. Walks it there over `dt` seconds.
+    pub fn move_mob(&mut self, entity: Entity, pos: Vec2, dt: f32) {
+        let Some(transform) = self.ecs.transforms.get_mut(entity) else {
+            return;
+        };
+        let (old_pos, old_chunk) = (transform.pos, Self::chunk_pos_at(transform.pos));
+        transform.pos = pos;
+        if let Some(animation) = self.ecs.animations.get_mut(entity) {
+            animation.face(vec2(pos.x - old_pos.x, 0.0));
+            animation.update(dt, pos != old_pos, 0.15);
+        }
+
+        let new_chunk = Self::chunk_pos_at(pos).filter(|pos| self.chunks.contains_key(pos));
+        if let (Some(old_chunk), Some(new_chunk)) = (old_chunk, new_chunk.filter(|new_chunk| Some(*new_chunk) != old_chunk)) {
+            if let Some(chunk) = self.chunks.get_mut(&old_chunk) {
+                chunk.mobs.retain(|mob| *mob != entity);
+            }
+            self.chunks.get_mut(&new_chunk).unwrap().mobs.push(entity);
+        }
+    }
+
+    pub fn mob_at(&self, world_pos: Vec2) -> Option<Entity> {
+        self.mobs().find(|entity| self.ecs.transforms.get(*entity).is_some_and(|transform| transform.rect().contains(world_pos)))
+    }
+
+    /// Damages the mob at `world_pos`. Returns its drops once it is dead and
+    /// removed from the world.
+    pub fn hit_mob(&mut self, world_pos: Vec2, damage: i32) -> Option<Vec<ItemStack>> {
+        let entity = self.mob_at(world_pos)?;
+        let drops = self.damage_entity(entity, damage)?;
+        for chunk in self.chunks.values_mut() {
+            chunk.mobs.retain(|mob| *mob != entity);
+        }
+        Some(drops)
+    }
+
+    /// Highest contact damage of the blocks and mobs overlapping `rect`.
+    pub fn contact_damage(&self, rect: Rect) -> i32 {
+        let nearby = self.entities_near(rect.center(), rect.w.max(rect.h) + 64.0);
+        systems::contact_damage(&self.ecs, nearby.into_iter(), rect)
+    }
+
+    /// Runs the mobs' AI and movement and sorts them into the chunks they
+    /// walked into. Night mobs disappear during the day. `players` are the
+    /// centers of the players mobs react to.
+    pub fn update_mobs(&mut self, dt: f32, players: &[Vec2]) {
+        let night = self.is_night();
+        let mobs: Vec<(ChunkPos, Entity)> = self.chunks.values()
+            .flat_map(|chunk| chunk.mobs.iter().map(|entity| (chunk.pos, *entity)))
+            .collect();
+
+        for (chunk_pos, entity) in mobs {
+            let Some(mob_type) = self.ecs.mobs.get(entity).and_then(|mob| self.mob_types.get(&mob.kind)) else {
+                continue;
+            };
+            if mob_type.config.night && !night {
+                self.chunks.get_mut(&chunk_pos).unwrap().mobs.retain(|mob| *mob != entity);
+                self.ecs.despawn(entity);
+                continue;
+            }
+            let hostile = mob_type.config.hostile;
+            let speed = mob_type.config.speed;
+
+            let Some(center) = self.ecs.transforms.get(entity).map(|transform| transform.center()) else {
+                continue;
+            };
+            let target = players.iter()
+                .copied()
+                .filter(|player| player.distance(center) <= MOB_CHASE_DISTANCE)
+                .min_by(|a, b| a.distance(center).total_cmp(&b.distance(center)));
+            mob::update(self, entity, dt, hostile, speed, target);
+
+            let new_chunk = self.ecs.transforms.get(entity)
+                .and_then(|transform| Self::chunk_pos_at(transform.pos))
+                .filter(|pos| self.chunks.contains_key(pos));
+            if let Some(new_chunk) = new_chunk.filter(|pos| *pos != chunk_pos) {
+                self.chunks.get_mut(&chunk_pos).unwrap().mobs.retain(|mob| *mob != entity);
+                self.chunks.get_mut(&new_chunk).unwrap().mobs.push(entity);
+            }
+        }
+    }
+
+    /// Blocks and mobs from every loaded chunk touching the square of
+    /// `radius` around `world_pos`.
+    pub fn entities_near(&self, world_pos: Vec2, radius: f32) -> Vec<Entity> {
+        let min = (world_pos - vec2(radius, radius)).max(Vec2::ZERO);
+        let max = world_pos + vec2(radius, radius);
+        let (min_x, min_y) = ((min.x / CHUNK_PIXELS) as usize, (min.y / CHUNK_PIXELS) as usize);
+        let (max_x, max_y) = ((max.x / CHUNK_PIXELS) as usize, (max.y / CHUNK_PIXELS) as usize);
+
+        let mut entities = Vec::new();
+        for chunk_y in min_y..=max_y {
+            for chunk_x in min_x..=max_x {
+                if let Some(chunk) = self.chunks.get(&(chunk_x, chunk_y)) {
+                    entities.extend(chunk.blocks.iter().chain(&chunk.mobs)
+                        .copied()
+                        .filter(|entity| self.ecs.transforms.get(*entity).is_some_and(|transform| {
+                            let dx = (transform.pos.x - world_pos.x).abs();
+                            let dy = (transform.pos.y - world_pos.y).abs();
+                            dx < radius && dy < radius
+                        })));
                 }
             }
         }
+        entities
     }
-    pub async fn update_chunks(&mut self, player_pos: Vec2, generator: &mut WorldGenerator, tile_textures: &TileTextures, block_textures: &BlockTextures) {
-        let player_chunk_x = (player_pos.x / CHUNK_PIXELS) as usize;
-        let player_chunk_y = (player_pos.y / CHUNK_PIXELS) as usize;
-        let render_distance = RENDER_DISTANCE;
+
+    /// Registers which mod tiles are solid or emit light.
+    pub fn add_tile_properties(&mut self, tiles: &[TileMod]) {
+        for tile in tiles {
+            if tile.solid {
+                self.solid_tiles.insert(tile.id.clone());
+            }
+            if tile.light > 0 {
+                self.light_tiles.insert(tile.id.clone(), tile.light.min(MAX_LIGHT));
+            }
+        }
+    }
+
+    /// Light emitted by the tile itself, 0 for most tiles.
+    pub fn tile_light(&self, tile: &Tile) -> u8 {
+        match &tile.state {
+            TileState::Custom(id) => self.light_tiles.get(id).copied().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// Whether nothing can walk onto the tile. Missing tiles, outside the
+    /// world or in chunks that aren't loaded, are solid.
+    pub fn is_solid_tile(&self, tile: Option<&Tile>) -> bool {
+        match tile.map(|tile| &tile.state) {
+            None => true,
+            Some(TileState::Custom(id)) => self.solid_tiles.contains(id),
+            Some(_) => false,
+        }
+    }
+
+    /// Colliders of the blocks and solid tiles near `world_pos`, which
+    /// nothing can walk through.
+    pub fn solid_rects_near(&self, world_pos: Vec2, radius: f32) -> Vec<Rect> {
+        let mut rects: Vec<Rect> = self.entities_near(world_pos, radius).into_iter()
+            .filter_map(|entity| {
+                let collider = self.ecs.colliders.get(entity)?;
+                Some(collider.rect(self.ecs.transforms.get(entity)?))
+            })
+            .collect();
+        rects.extend(self.tile_rects_near(world_pos, radius, |tile| self.is_solid_tile(tile)));
+        rects
+    }
+
+    /// Tiles within `radius` of `world_pos` for which `solid` returns `true`,
+    /// including missing ones outside the world or in unloaded chunks.
+    pub fn tile_rects_near(&self, world_pos: Vec2, radius: f32, solid: impl Fn(Option<&Tile>) -> bool) -> Vec<Rect> {
+        let min = ((world_pos - vec2(radius, radius)) / TILE_SIZE).floor();
+        let max = ((world_pos + vec2(radius, radius)) / TILE_SIZE).floor();
+
+        let mut rects = Vec::new();
+        for tile_y in min.y as i32..=max.y as i32 {
+            for tile_x in min.x as i32..=max.x as i32 {
+                let pos = vec2(tile_x as f32, tile_y as f32) * TILE_SIZE;
+                let inside = tile_x >= 0 && tile_y >= 0 && (tile_x as usize) < self.width && (tile_y as usize) < self.height;
+                let tile = if inside { self.tile_at(pos) } else { None };
+                if solid(tile) {
+                    rects.push(Rect::new(pos.x, pos.y, TILE_SIZE, TILE_SIZE));
+                }
+            }
+        }
+        rects
+    }
+
+    /// Keeps chunks loaded around every position in `player_positions`.
+    pub fn update_chunks(&mut self, player_positions: &[Vec2], render_distance: f32, generator: &mut WorldGenerator) {
+        let player_chunks: Vec<ChunkPos> = player_positions.iter()
+            .map(|pos| ((pos.x / CHUNK_PIXELS) as usize, (pos.y / CHUNK_PIXELS) as usize))
+            .collect();
         
-        self.remove_far_chunks(player_chunk_x, player_chunk_y, render_distance);
+        self.remove_far_chunks(&player_chunks, render_distance);
         
-        self.create_nearby_chunks(
-            player_chunk_x,
-            player_chunk_y,
-            render_distance,
-            generator,
-            tile_textures,
-            block_textures
-        ).await;
+        for (player_chunk_x, player_chunk_y) in player_chunks {
+            self.create_nearby_chunks(
+                player_chunk_x,
+                player_chunk_y,
+                render_distance,
+                generator
+            );
+        }
     }
     
-    fn remove_far_chunks(&mut self, player_x: usize, player_y: usize, distance: f32) {
-        let to_remove: Vec<_> = self.chunks.iter()
-        .filter(|chunk| {
-            let dx = chunk.pos.0 as isize - player_x as isize;
-            let dy = chunk.pos.1 as isize - player_y as isize;
+    pub fn remove_far_chunks(&mut self, player_chunks: &[ChunkPos], distance: f32) {
+        let to_remove: Vec<_> = self.chunks.keys()
+        .filter(|pos| player_chunks.iter().all(|(player_x, player_y)| {
+            let dx = pos.0 as isize - *player_x as isize;
+            let dy = pos.1 as isize - *player_y as isize;
             ((dx * dx + dy * dy) as f32).sqrt() > distance
-        })
-            .map(|chunk| chunk.pos)
+        }))
+            .copied()
             .collect();
             
-        for pos in &to_remove {
-            if let Some(idx) = self.chunks.iter().position(|c| c.pos == *pos) {
-                let chunk = self.chunks.remove(idx);
-                self.chunk_pool.return_chunk(chunk);
-            }
+        for pos in to_remove {
+            self.unload_chunk(pos);
         }
     }
     
-    async fn create_nearby_chunks(&mut self, player_x: usize, player_y: usize, distance: f32, 
-        generator: &mut WorldGenerator, tile_textures: &TileTextures, block_textures: &BlockTextures) {
-        for dy in -2..=2 {
-            for dx in -2..=2 {
+    fn create_nearby_chunks(&mut self, player_x: usize, player_y: usize, distance: f32, 
+        generator: &mut WorldGenerator) {
+        let radius = distance.ceil() as isize;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
                 if ((dx * dx + dy * dy) as f32).sqrt() <= distance {
                     let chunk_x = player_x as isize + dx;
                     let chunk_y = player_y as isize + dy;
@@ -92,33 +572,30 @@ impl World {
                         self.create_chunk_if_needed(
                             chunk_x as usize,
                             chunk_y as usize,
-                            generator,
-                            tile_textures,
-                            block_textures
-                        ).await;
+                            generator
+                        );
                     }
                 }
             }
         }
     }
-    async fn create_chunk_if_needed(&mut self, x: usize, y: usize, 
-        generator: &mut WorldGenerator, 
-        tile_textures: &TileTextures, 
-        block_textures: &BlockTextures) {
+    fn create_chunk_if_needed(&mut self, x: usize, y: usize, 
+        generator: &mut WorldGenerator) {
             
-            if !self.chunks.iter().any(|c| c.pos.0 == x && c.pos.1 == y) {
-                if let Some(mut chunk) = self.chunk_pool.get_chunk() {
-                    chunk.pos = (x, y);
-                    generator.generate_chunk_data(&mut chunk, tile_textures.clone(), block_textures);
-                    self.chunks.push(chunk);
+            if !self.chunks.contains_key(&(x, y)) {
+                if let Some(save) = self.edited_chunks.remove(&(x, y)) {
+                    let elapsed = self.growth_since(save.saved_at);
+                    let mut chunk = save.restore(&mut self.ecs, &self.mob_types);
+                    chunk.dirty = true;
+                    self.chunks.insert((x, y), chunk);
+                    self.grow_chunk((x, y), elapsed);
+                } else if let Some(mut chunk) = self.chunk_pool.get_chunk() {
+                    chunk.set_pos((x, y));
+                    generator.generate_chunk_data(&mut chunk, &mut self.ecs);
+                    self.chunks.insert((x, y), chunk);
                 } else {
-                    let new_chunk = generator.generate_chunk(
-                        x,
-                        y,
-                        tile_textures.clone(),
-                        block_textures
-                    ).await;
-                    self.chunks.push(new_chunk);
+                    let new_chunk = generator.generate_chunk(x, y, &mut self.ecs);
+                    self.chunks.insert((x, y), new_chunk);
                 }
             }
         }
@@ -0,0 +1,98 @@
+use macroquad::prelude::{KeyCode, MouseButton};
+use serde::{Deserialize, Serialize};
+
+use super::{gamepad::GamepadButton, keys, mouse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    UseItem,
+    BreakBlock,
+    NextItem,
+    PreviousItem,
+    Crafting,
+    ZoomIn,
+    ZoomOut,
+    ToggleDebug,
+    QuickSave,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 14] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::UseItem,
+        Action::BreakBlock,
+        Action::NextItem,
+        Action::PreviousItem,
+        Action::Crafting,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ToggleDebug,
+        Action::QuickSave,
+        Action::Pause,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::UseItem => "Use item",
+            Action::BreakBlock => "Break block",
+            Action::NextItem => "Next item",
+            Action::PreviousItem => "Previous item",
+            Action::Crafting => "Crafting",
+            Action::ZoomIn => "Zoom in",
+            Action::ZoomOut => "Zoom out",
+            Action::ToggleDebug => "Debug info",
+            Action::QuickSave => "Quick save",
+            Action::Pause => "Pause",
+        }
+    }
+
+    pub fn default_bindings(&self) -> Vec<Binding> {
+        use Binding::{Gamepad, Key, Mouse};
+        match self {
+            Action::MoveUp => vec![Key(KeyCode::W), Key(KeyCode::Up), Gamepad(GamepadButton::DPadUp)],
+            Action::MoveDown => vec![Key(KeyCode::S), Key(KeyCode::Down), Gamepad(GamepadButton::DPadDown)],
+            Action::MoveLeft => vec![Key(KeyCode::A), Key(KeyCode::Left), Gamepad(GamepadButton::DPadLeft)],
+            Action::MoveRight => vec![Key(KeyCode::D), Key(KeyCode::Right), Gamepad(GamepadButton::DPadRight)],
+            Action::UseItem => vec![Mouse(MouseButton::Left), Gamepad(GamepadButton::RightTrigger)],
+            Action::BreakBlock => vec![Mouse(MouseButton::Right), Gamepad(GamepadButton::LeftTrigger)],
+            Action::NextItem => vec![Key(KeyCode::E), Gamepad(GamepadButton::North)],
+            Action::PreviousItem => vec![Key(KeyCode::Q), Gamepad(GamepadButton::West)],
+            Action::Crafting => vec![Key(KeyCode::C), Gamepad(GamepadButton::East)],
+            Action::ZoomIn => vec![Key(KeyCode::Equal), Key(KeyCode::KpAdd), Gamepad(GamepadButton::RightBumper)],
+            Action::ZoomOut => vec![Key(KeyCode::Minus), Key(KeyCode::KpSubtract), Gamepad(GamepadButton::LeftBumper)],
+            Action::ToggleDebug => vec![Key(KeyCode::F3), Gamepad(GamepadButton::Select)],
+            Action::QuickSave => vec![Key(KeyCode::F5)],
+            Action::Pause => vec![Key(KeyCode::Escape), Gamepad(GamepadButton::Start)],
+        }
+    }
+}
+
+/// A key, mouse button or gamepad button an action is bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(#[serde(with = "keys")] KeyCode),
+    Mouse(#[serde(with = "mouse")] MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => keys::key_name(*key),
+            Binding::Mouse(button) => format!("Mouse {}", mouse::button_name(*button)),
+            Binding::Gamepad(button) => format!("Pad {:?}", button),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::consts::GAMEPAD_DEADZONE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Button and left stick state of all connected gamepads. Without the
+/// `gamepad` feature nothing is ever pressed.
+#[derive(Default)]
+pub struct Gamepads {
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<gilrs::Gilrs>,
+    down: HashSet<GamepadButton>,
+    pressed: Vec<GamepadButton>,
+    stick: Vec2,
+}
+
+impl Gamepads {
+    pub fn new() -> Self {
+        #[cfg(feature = "gamepad")]
+        {
+            let gilrs = match gilrs::Gilrs::new() {
+                Ok(gilrs) => Some(gilrs),
+                Err(e) => {
+                    println!("Gamepad support unavailable: {}", e);
+                    None
+                }
+            };
+            Self { gilrs, ..Default::default() }
+        }
+        #[cfg(not(feature = "gamepad"))]
+        Self::default()
+    }
+
+    /// Must be called once per frame before querying buttons.
+    pub fn update(&mut self) {
+        self.pressed.clear();
+
+        #[cfg(feature = "gamepad")]
+        if let Some(gilrs) = &mut self.gilrs {
+            use gilrs::{Axis, EventType};
+
+            while let Some(event) = gilrs.next_event() {
+                match event.event {
+                    EventType::ButtonPressed(button, _) => {
+                        if let Some(button) = GamepadButton::from_gilrs(button) {
+                            self.down.insert(button);
+                            self.pressed.push(button);
+                        }
+                    }
+                    EventType::ButtonReleased(button, _) => {
+                        if let Some(button) = GamepadButton::from_gilrs(button) {
+                            self.down.remove(&button);
+                        }
+                    }
+                    EventType::AxisChanged(Axis::LeftStickX, value, _) => self.stick.x = value,
+                    EventType::AxisChanged(Axis::LeftStickY, value, _) => self.stick.y = -value,
+                    EventType::Disconnected => {
+                        self.down.clear();
+                        self.stick = vec2(0.0, 0.0);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    pub fn is_down(&self, button: GamepadButton) -> bool {
+        self.down.contains(&button)
+    }
+
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    pub fn last_pressed(&self) -> Option<GamepadButton> {
+        self.pressed.last().copied()
+    }
+
+    /// Left stick position with the deadzone removed, y pointing down.
+    pub fn stick(&self) -> Vec2 {
+        if self.stick.length() < GAMEPAD_DEADZONE {
+            vec2(0.0, 0.0)
+        } else {
+            self.stick.clamp_length_max(1.0)
+        }
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadButton {
+    fn from_gilrs(button: gilrs::Button) -> Option<Self> {
+        use gilrs::Button;
+
+        Some(match button {
+            Button::South => GamepadButton::South,
+            Button::East => GamepadButton::East,
+            Button::North => GamepadButton::North,
+            Button::West => GamepadButton::West,
+            Button::LeftTrigger => GamepadButton::LeftBumper,
+            Button::RightTrigger => GamepadButton::RightBumper,
+            Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+            Button::RightTrigger2 => GamepadButton::RightTrigger,
+            Button::Select => GamepadButton::Select,
+            Button::Start => GamepadButton::Start,
+            Button::LeftThumb => GamepadButton::LeftStick,
+            Button::RightThumb => GamepadButton::RightStick,
+            Button::DPadUp => GamepadButton::DPadUp,
+            Button::DPadDown => GamepadButton::DPadDown,
+            Button::DPadLeft => GamepadButton::DPadLeft,
+            Button::DPadRight => GamepadButton::DPadRight,
+            _ => return None,
+        })
+    }
+}
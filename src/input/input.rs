@@ -0,0 +1,128 @@
+use std::{collections::HashMap, error::Error, fs};
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::consts::CONTROLS_FILE;
+
+use super::{action::{Action, Binding}, gamepad::Gamepads};
+
+/// Bindings for every action, stored in `controls.json`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InputMap {
+    pub bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL.iter()
+                .map(|action| (*action, action.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl InputMap {
+    /// Falls back to the defaults for a missing file and for actions the
+    /// file does not mention.
+    pub fn load(filename: &str) -> Result<Self, Box<dyn Error>> {
+        let mut map = Self::default();
+        if let Ok(json) = fs::read_to_string(filename) {
+            let saved: InputMap = serde_json::from_str(&json)?;
+            map.bindings.extend(saved.bindings);
+        }
+        Ok(map)
+    }
+
+    pub fn save(&self, filename: &str) -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(filename, json)?;
+        Ok(())
+    }
+
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map(|b| b.as_slice()).unwrap_or(&[])
+    }
+
+    /// Replaces the binding in `slot`, or adds it if the action has fewer bindings.
+    pub fn rebind(&mut self, action: Action, slot: usize, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        if slot < bindings.len() {
+            bindings[slot] = binding;
+        } else {
+            bindings.push(binding);
+        }
+    }
+}
+
+pub struct Input {
+    pub map: InputMap,
+    pub gamepads: Gamepads,
+}
+
+impl Input {
+    pub fn load() -> Self {
+        let map = InputMap::load(CONTROLS_FILE).unwrap_or_else(|e| {
+            println!("Failed to load {}: {}, using default controls", CONTROLS_FILE, e);
+            InputMap::default()
+        });
+
+        Self {
+            map,
+            gamepads: Gamepads::new(),
+        }
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = self.map.save(CONTROLS_FILE) {
+            println!("Failed to save {}: {}", CONTROLS_FILE, e);
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.gamepads.update();
+    }
+
+    pub fn is_down(&self, action: Action) -> bool {
+        self.map.get(action).iter().any(|binding| match binding {
+            Binding::Key(key) => is_key_down(*key),
+            Binding::Mouse(button) => is_mouse_button_down(*button),
+            Binding::Gamepad(button) => self.gamepads.is_down(*button),
+        })
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.map.get(action).iter().any(|binding| match binding {
+            Binding::Key(key) => is_key_pressed(*key),
+            Binding::Mouse(button) => is_mouse_button_pressed(*button),
+            Binding::Gamepad(button) => self.gamepads.is_pressed(*button),
+        })
+    }
+
+    /// Movement direction from the move actions and the left stick.
+    pub fn movement(&self) -> Vec2 {
+        let mut movement = vec2(0.0, 0.0);
+        if self.is_down(Action::MoveUp) { movement.y -= 1.0; }
+        if self.is_down(Action::MoveDown) { movement.y += 1.0; }
+        if self.is_down(Action::MoveLeft) { movement.x -= 1.0; }
+        if self.is_down(Action::MoveRight) { movement.x += 1.0; }
+
+        if movement == Vec2::ZERO {
+            movement = self.gamepads.stick();
+        }
+        movement
+    }
+
+    /// Whatever key or button was pressed this frame, for rebinding.
+    pub fn last_pressed(&self) -> Option<Binding> {
+        get_last_key_pressed()
+            .filter(|key| super::keys::BINDABLE_KEYS.contains(key))
+            .map(Binding::Key)
+            .or_else(|| super::mouse::BINDABLE_BUTTONS.iter()
+                .copied()
+                .find(|button| is_mouse_button_pressed(*button))
+                .map(Binding::Mouse))
+            .or_else(|| self.gamepads.last_pressed().map(Binding::Gamepad))
+    }
+}
@@ -0,0 +1,5 @@
+pub mod action;
+pub mod gamepad;
+pub mod input;
+pub mod keys;
+pub mod mouse;
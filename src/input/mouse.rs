@@ -0,0 +1,21 @@
+use macroquad::prelude::MouseButton;
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+pub const BINDABLE_BUTTONS: [MouseButton; 3] = [MouseButton::Left, MouseButton::Middle, MouseButton::Right];
+
+pub fn button_name(button: MouseButton) -> String {
+    format!("{:?}", button)
+}
+
+pub fn button_from_name(name: &str) -> Option<MouseButton> {
+    BINDABLE_BUTTONS.iter().copied().find(|button| button_name(*button) == name)
+}
+
+pub fn serialize<S: Serializer>(button: &MouseButton, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&button_name(*button))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MouseButton, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    button_from_name(&name).ok_or_else(|| de::Error::custom(format!("unknown mouse button: {}", name)))
+}
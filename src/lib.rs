@@ -0,0 +1,7 @@
+pub mod game;
+pub mod input;
+pub mod menu;
+pub mod net;
+pub mod save;
+pub mod server;
+pub mod utils;
\ No newline at end of file
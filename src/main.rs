@@ -1,28 +1,25 @@
 
 
-use game::gamestate::GameState;
+use game_lib::{game::gamestate::GameState, input::{action::Action, input::Input}, menu::{menu::{Menu, MenuAction}, pause::{PauseAction, PauseMenu}}, utils::{consts::{SAVE_FILE, SERVER_PORT}, generate_seed::generate_seed, mod_loader::ModLoader, settings::Settings, system::SystemInfo}};
 use macroquad::prelude::*;
-use menu::menu::{Menu, MenuAction};
-use utils::{generate_seed::generate_seed, mod_loader::ModLoader, system::SystemInfo};
 
-mod save;
-mod utils;
-mod menu;
-mod game;
 fn window_conf() -> Conf {
+    let settings = Settings::load();
+    let swap_interval = Some(if settings.vsync { 1 } else { 0 });
+
     Conf {
         window_title: "Game".to_owned(),
-        window_width: 800,
-        window_height: 600,
+        window_width: settings.window_width,
+        window_height: settings.window_height,
         window_resizable: true,
-        fullscreen: false,
+        fullscreen: settings.fullscreen,
         platform: {
             #[cfg(target_os = "linux")]
             {
                 miniquad::conf::Platform {
-                    linux_backend: miniquad::conf::LinuxBackend::X11,
-                    linux_x11_gl: true,
-                    swap_interval: Some(1),
+                    linux_backend: miniquad::conf::LinuxBackend::X11Only,
+                    linux_x11_gl: miniquad::conf::LinuxX11Gl::GLXWithEGLFallback,
+                    swap_interval,
                     ..Default::default()
                 }
             }
@@ -30,14 +27,14 @@ fn window_conf() -> Conf {
             {
                 miniquad::conf::Platform {
                     apple_gfx_api: miniquad::conf::AppleGfxApi::Metal,
-                    swap_interval: Some(1),
+                    swap_interval,
                     ..Default::default()
                 }
             }
             #[cfg(target_os = "windows")]
             {
                 miniquad::conf::Platform {
-                    swap_interval: Some(1),
+                    swap_interval,
                     ..Default::default()
                 }
             }
@@ -49,38 +46,110 @@ fn window_conf() -> Conf {
 #[macroquad::main(window_conf)]
 async fn main() {
 
-    let mut game_state = None;
+    let mut game_state: Option<GameState> = None;
+    let mut pause_menu: Option<PauseMenu> = None;
     let mut menu = Menu::new().await;
     let mut system_info = SystemInfo::new();
+    let mut input = Input::load();
+    let mut settings = Settings::load();
     let mut loader = ModLoader::new();
     let _ = loader.load_mods();
     menu.init().await;
+    menu.is_debug = settings.show_debug;
+
+    let args: Vec<String> = std::env::args().collect();
+    let server_address = args.iter()
+        .position(|arg| arg == "--connect")
+        .and_then(|i| args.get(i + 1).cloned())
+        .unwrap_or_else(|| format!("127.0.0.1:{}", SERVER_PORT));
 
     loop {
         let delta_time = get_frame_time();
         system_info.update();
+        input.update();
 
         match &mut game_state {
             None => {
-                match menu.update() {
+                match menu.update(&mut input, &mut settings) {
                     MenuAction::NewGame => {
                         let biomes = loader.get_biomes().to_vec();
                         let tiles = loader.get_tiles().to_vec();
                         
                         let seed = generate_seed();
-                        game_state = Some(GameState::new(seed, biomes, tiles).await);
+                        let recipes = loader.get_recipes().to_vec();
+                        let mobs = loader.get_mobs().to_vec();
+                        let emitters = loader.get_emitters().to_vec();
+                        let mut state = GameState::new(seed, biomes, tiles, recipes, mobs, emitters).await;
+                        state.simulation.world.clock.day_length = settings.day_length;
+                        state.apply_settings(&settings);
+                        game_state = Some(state);
+                    }
+                    MenuAction::LoadGame => {
+                        let biomes = loader.get_biomes().to_vec();
+                        let tiles = loader.get_tiles().to_vec();
+
+                        let recipes = loader.get_recipes().to_vec();
+                        let mobs = loader.get_mobs().to_vec();
+                        let emitters = loader.get_emitters().to_vec();
+
+                        match GameState::load_game(SAVE_FILE, biomes, tiles, recipes, mobs, emitters).await {
+                            Ok(mut state) => {
+                                state.apply_settings(&settings);
+                                game_state = Some(state);
+                            }
+                            Err(e) => println!("Failed to load {}: {}", SAVE_FILE, e),
+                        }
+                    }
+                    MenuAction::JoinGame => {
+                        let biomes = loader.get_biomes().to_vec();
+                        let tiles = loader.get_tiles().to_vec();
+
+                        let recipes = loader.get_recipes().to_vec();
+                        let mobs = loader.get_mobs().to_vec();
+                        let emitters = loader.get_emitters().to_vec();
+
+                        match GameState::join(&server_address, biomes, tiles, recipes, mobs, emitters, loader.get_mod_ids()).await {
+                            Ok(mut state) => {
+                                state.apply_settings(&settings);
+                                game_state = Some(state);
+                            }
+                            Err(e) => println!("Failed to join {}: {}", server_address, e),
+                        }
                     }
                     MenuAction::Exit => break,
                     MenuAction::None => {}
-                    _ => {}
                 }
-                menu.draw();
+                menu.draw(&input, &settings);
             }
             Some(state) => {
-                if is_key_pressed(KeyCode::Escape) {
-                    game_state = None;
-                } else {
-                    state.update(delta_time, &system_info).await;
+                let just_paused = pause_menu.is_none() && input.is_pressed(Action::Pause);
+                if just_paused {
+                    pause_menu = Some(PauseMenu::new(menu.button_textures()));
+                }
+                state.paused = pause_menu.is_some();
+                state.update(delta_time, &input, &system_info);
+
+                if let (false, Some(pause)) = (just_paused, &mut pause_menu) {
+                    match pause.update(&mut input, &mut settings, state.has_unsaved_changes()) {
+                        PauseAction::Resume => pause_menu = None,
+                        PauseAction::Save => state.quick_save(),
+                        PauseAction::SettingsChanged => state.apply_settings(&settings),
+                        PauseAction::SaveAndQuit => {
+                            state.quick_save();
+                            if !state.has_unsaved_changes() {
+                                game_state = None;
+                                pause_menu = None;
+                            }
+                        }
+                        PauseAction::Quit => {
+                            game_state = None;
+                            pause_menu = None;
+                        }
+                        PauseAction::None => {}
+                    }
+                }
+                if let Some(pause) = &pause_menu {
+                    pause.draw(&input, &settings);
                 }
             }
         }
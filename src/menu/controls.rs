@@ -0,0 +1,112 @@
+use macroquad::prelude::*;
+
+use crate::input::{action::Action, input::{Input, InputMap}};
+
+use super::button::{button::Button, textures::ButtonTextures};
+
+const SLOTS: usize = 3;
+const ROW_HEIGHT: f32 = 26.0;
+const SLOT_WIDTH: f32 = 110.0;
+
+/// Rebinding screen opened from the CONTROLS button. Click a slot and press
+/// a key or button to bind it, right click to clear it.
+pub struct ControlsScreen {
+    buttons: Vec<Button>,
+    rebinding: Option<(Action, usize)>,
+}
+
+impl ControlsScreen {
+    pub fn new(textures: &ButtonTextures) -> Self {
+        let button_width = 200.0;
+        let button_y = 140.0 + Action::ALL.len() as f32 * ROW_HEIGHT;
+
+        Self {
+            buttons: vec![
+                Button::new("RESET", vec2(screen_width() / 2.0 - button_width - 10.0, button_y), textures.clone()),
+                Button::new("BACK", vec2(screen_width() / 2.0 + 10.0, button_y), textures.clone()),
+            ],
+            rebinding: None,
+        }
+    }
+
+    fn slot_rect(row: usize, slot: usize) -> Rect {
+        Rect::new(
+            screen_width() / 2.0 - 60.0 + slot as f32 * SLOT_WIDTH,
+            120.0 + row as f32 * ROW_HEIGHT,
+            SLOT_WIDTH - 10.0,
+            ROW_HEIGHT - 6.0,
+        )
+    }
+
+    /// Returns `true` once the player leaves the screen. Bindings are saved then.
+    pub fn update(&mut self, input: &mut Input) -> bool {
+        if let Some((action, slot)) = self.rebinding {
+            if let Some(binding) = input.last_pressed() {
+                input.map.rebind(action, slot, binding);
+                self.rebinding = None;
+            }
+            return false;
+        }
+
+        let mouse = Vec2::from(mouse_position());
+        for (row, action) in Action::ALL.iter().enumerate() {
+            for slot in 0..SLOTS {
+                if !Self::slot_rect(row, slot).contains(mouse) {
+                    continue;
+                }
+                if is_mouse_button_pressed(MouseButton::Left) {
+                    self.rebinding = Some((*action, slot));
+                } else if is_mouse_button_pressed(MouseButton::Right) {
+                    if let Some(bindings) = input.map.bindings.get_mut(action) {
+                        if slot < bindings.len() {
+                            bindings.remove(slot);
+                        }
+                    }
+                }
+            }
+        }
+
+        for button in &mut self.buttons {
+            if button.is_clicked() {
+                match button.text.as_str() {
+                    "RESET" => input.map = InputMap::default(),
+                    "BACK" => {
+                        input.save();
+                        return true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        false
+    }
+
+    pub fn draw(&self, input: &Input) {
+        let title = "CONTROLS";
+        let title_width = measure_text(title, None, 40, 1.0).width;
+        draw_text(title, (screen_width() - title_width) / 2.0, 100.0, 40.0, WHITE);
+
+        let mouse = Vec2::from(mouse_position());
+        for (row, action) in Action::ALL.iter().enumerate() {
+            let label = Self::slot_rect(row, 0);
+            draw_text(action.name(), screen_width() / 2.0 - 240.0, label.y + 15.0, 18.0, WHITE);
+
+            let bindings = input.map.get(*action);
+            for slot in 0..SLOTS {
+                let rect = Self::slot_rect(row, slot);
+                let text = if self.rebinding == Some((*action, slot)) {
+                    "...".to_owned()
+                } else {
+                    bindings.get(slot).map(|b| b.name()).unwrap_or_default()
+                };
+                let alpha = if rect.contains(mouse) { 0.6 } else { 0.4 };
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.0, 0.0, 0.0, alpha));
+                draw_text(&text, rect.x + 6.0, rect.y + 15.0, 16.0, WHITE);
+            }
+        }
+
+        for button in &self.buttons {
+            button.draw();
+        }
+    }
+}
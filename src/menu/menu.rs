@@ -1,19 +1,25 @@
-use crate::{game::{entity::block::textures::BlockTextures, utils::draw::DrawBatch, world::{generator::generator::WorldGenerator, tile::tile::Tile, world::World}}, utils::{consts::{CHUNK_SIZE, MENU_WORLD_SIZE, TILE_SIZE}, generate_seed::generate_seed, system::SystemInfo}};
+use crate::{input::{action::Action, input::Input}, utils::settings::Settings, game::{entity::block::textures::BlockTextures, utils::{animation::AnimationClock, draw::DrawBatch, render::{DepthQueue, SpriteTextures}}, world::{generator::generator::WorldGenerator, tile::{textures::TileTextures, tile::Tile}, world::World}}, utils::{consts::{CHUNK_SIZE, MENU_WORLD_SIZE, SNOW_TEMPERATURE, TILE_SIZE}, generate_seed::generate_seed, system::SystemInfo}};
 use macroquad::prelude::*;
 
 use super::button::button::Button;
 use super::button::textures::ButtonTextures;
+use super::controls::ControlsScreen;
+use super::settings::SettingsScreen;
 
 #[derive(Debug, Clone, Copy)]
 pub enum MenuAction {
     None,
     NewGame,
     LoadGame,
+    JoinGame,
     Exit,
 }
 
 pub struct Menu {
     background_world: World,
+    tile_textures: TileTextures,
+    block_textures: BlockTextures,
+    animation_clock: AnimationClock,
     buttons: Vec<Button>,
     seed: u32,
     pub camera: Camera2D,
@@ -21,6 +27,8 @@ pub struct Menu {
     button_textures: ButtonTextures,
     system_info: SystemInfo,
     state: MenuState,
+    controls: Option<ControlsScreen>,
+    settings: Option<SettingsScreen>,
     //world_settings: Option<WorldSettings>, 
     //available_saves: Vec<SaveInfo>,
 }
@@ -28,7 +36,9 @@ pub struct Menu {
 pub enum MenuState {
     Main,
     NewGame,
-    LoadGame
+    LoadGame,
+    Controls,
+    Settings,
 }
 /*pub struct WorldSettings {
     seed: u32,
@@ -54,8 +64,8 @@ impl Menu {
 
         for y in 0..chunks_y {
             for x in 0..chunks_x {
-                let chunk = generator.generate_chunk(x, y, tile_textures.clone(), &block_textures).await;
-                background_world.chunks.push(chunk);
+                let chunk = generator.generate_chunk(x, y, &mut background_world.ecs);
+                background_world.chunks.insert((x, y), chunk);
             }
         }
 
@@ -68,6 +78,9 @@ impl Menu {
 
         Self {
             background_world,
+            tile_textures,
+            block_textures,
+            animation_clock: AnimationClock::default(),
             buttons: Vec::new(),
             seed,
             button_textures: ButtonTextures::default(),
@@ -75,23 +88,58 @@ impl Menu {
             is_debug: false,
             system_info: SystemInfo::new(),
             state: MenuState::Main,
+            controls: None,
+            settings: None,
             //world_settings: None,
             //available_saves: Vec::new()
         }
     }
-    pub fn update(&mut self) -> MenuAction {
+    pub fn update(&mut self, input: &mut Input, settings: &mut Settings) -> MenuAction {
+        self.system_info.update();
+        self.animation_clock.advance(get_frame_time());
+
+        match self.state {
+            MenuState::Controls => {
+                if let Some(controls) = &mut self.controls {
+                    if controls.update(input) {
+                        self.state = MenuState::Main;
+                    }
+                }
+                return MenuAction::None;
+            }
+            MenuState::Settings => {
+                if let Some(screen) = &mut self.settings {
+                    if screen.update(settings, input) {
+                        self.is_debug = settings.show_debug;
+                        self.state = MenuState::Main;
+                    }
+                }
+                return MenuAction::None;
+            }
+            _ => {}
+        }
+
         for button in &mut self.buttons {
             if button.is_clicked() {
                 return match button.text.as_str() {
                     "NEW GAME" => MenuAction::NewGame,
+                    "LOAD GAME" => MenuAction::LoadGame,
+                    "JOIN GAME" => MenuAction::JoinGame,
+                    "SETTINGS" => {
+                        self.state = MenuState::Settings;
+                        MenuAction::None
+                    }
+                    "CONTROLS" => {
+                        self.state = MenuState::Controls;
+                        MenuAction::None
+                    }
                     "EXIT" => MenuAction::Exit,
                     _ => MenuAction::None,
                 };
             }
         }
 
-        self.system_info.update();
-        if is_key_pressed(KeyCode::F3) {
+        if input.is_pressed(Action::ToggleDebug) {
             self.is_debug = !self.is_debug;
         }
         MenuAction::None
@@ -110,26 +158,39 @@ impl Menu {
 
     }
 
-    pub fn draw(&self) {
+    pub fn draw(&self, input: &Input, settings: &Settings) {
         set_camera(&self.camera);
 
         // Рисуем фоновый мир
         let mut batch = DrawBatch::new();
-        for chunk in &self.background_world.chunks {
+        let mut queue = DepthQueue::new();
+        for chunk in self.background_world.chunks.values() {
             for tile in &chunk.tiles {
-                tile.draw(&chunk.tiles, CHUNK_SIZE, &mut batch);
+                tile.draw(&self.tile_textures, &chunk.tiles, CHUNK_SIZE, SNOW_TEMPERATURE as f32, self.animation_clock.time, &mut batch);
             }
-            batch.draw();
 
             for block in &chunk.blocks {
-                block.draw();
+                queue.add(&self.background_world.ecs, *block);
             }
         }
+        batch.draw();
+        queue.draw(&self.background_world.ecs, &SpriteTextures {
+            blocks: &self.block_textures,
+            mobs: None,
+            players: None,
+            time: self.animation_clock.time,
+        });
 
         set_default_camera();
 
         draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.5));
 
+        match (&self.state, &self.controls, &self.settings) {
+            (MenuState::Controls, Some(controls), _) => return controls.draw(input),
+            (MenuState::Settings, _, Some(screen)) => return screen.draw(settings, input),
+            _ => {}
+        }
+
         let title = "Game";
         let title_size = 40.0;
         let title_width = measure_text(title, None, title_size as u16, 1.0).width;
@@ -147,6 +208,10 @@ impl Menu {
         if self.is_debug { self.draw_debug_info(&self.system_info);}
     }
 
+    pub fn button_textures(&self) -> &ButtonTextures {
+        &self.button_textures
+    }
+
     pub async fn init(&mut self) {
         self.button_textures = ButtonTextures {
             start: load_texture("assets/textures/gui/button_start.png").await.unwrap(),
@@ -160,13 +225,16 @@ impl Menu {
 
         self.buttons = vec![
             Button::new("NEW GAME", vec2(screen_width() / 2.0 - button_width / 2.0, button_y), self.button_textures.clone()),
-            //Button::new("LOAD", vec2(screen_width() / 2.0 - button_width / 2.0, button_y + button_spacing), self.button_textures.clone()),
-            //Button::new("SETTINGS", vec2(screen_width() / 2.0 - button_width / 2.0, button_y + button_spacing * 2.0), self.button_textures.clone()),
-            //Button::new("CONTROLS", vec2(screen_width() / 2.0 - button_width / 2.0, button_y + button_spacing * 3.0), self.button_textures.clone()),
+            Button::new("LOAD GAME", vec2(screen_width() / 2.0 - button_width / 2.0, button_y + button_spacing), self.button_textures.clone()),
+            Button::new("JOIN GAME", vec2(screen_width() / 2.0 - button_width / 2.0, button_y + button_spacing * 2.0), self.button_textures.clone()),
+            Button::new("SETTINGS", vec2(screen_width() / 2.0 - button_width / 2.0, button_y + button_spacing * 3.0), self.button_textures.clone()),
+            Button::new("CONTROLS", vec2(screen_width() / 2.0 - button_width / 2.0, button_y + button_spacing * 4.0), self.button_textures.clone()),
             //Button::new("AUTHORS", vec2(screen_width() / 2.0 - button_width / 2.0, button_y + button_spacing * 4.0), self.button_textures.clone()),
             Button::new("EXIT", vec2(screen_width() / 2.0 - button_width / 2.0, button_y + button_spacing * 5.0), self.button_textures.clone()),
         ];
+        self.controls = Some(ControlsScreen::new(&self.button_textures));
+        self.settings = Some(SettingsScreen::new(&self.button_textures));
 
     }
 
-}
\ No newline at end of file
+}
\ No newline at end of file
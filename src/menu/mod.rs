@@ -1,2 +1,5 @@
 pub mod button;
-pub mod menu;
\ No newline at end of file
+pub mod controls;
+pub mod menu;
+pub mod pause;
+pub mod settings;
\ No newline at end of file
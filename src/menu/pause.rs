@@ -0,0 +1,121 @@
+use macroquad::prelude::*;
+
+use crate::{input::{action::Action, input::Input}, utils::settings::Settings};
+
+use super::{button::{button::Button, textures::ButtonTextures}, settings::SettingsScreen};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PauseAction {
+    None,
+    Resume,
+    Save,
+    /// Settings were closed and may have changed.
+    SettingsChanged,
+    Quit,
+    SaveAndQuit,
+}
+
+enum PauseState {
+    Main,
+    Settings,
+    ConfirmQuit,
+}
+
+/// Overlay shown over the frozen game when the pause action is pressed.
+pub struct PauseMenu {
+    buttons: Vec<Button>,
+    confirm_buttons: Vec<Button>,
+    settings: SettingsScreen,
+    state: PauseState,
+}
+
+impl PauseMenu {
+    pub fn new(textures: &ButtonTextures) -> Self {
+        let button_y = 150.0;
+        let button_spacing = 60.0;
+        let x = screen_width() / 2.0 - 100.0;
+        let button = |text: &str, i: f32| Button::new(text, vec2(x, button_y + button_spacing * i), textures.clone());
+
+        Self {
+            buttons: vec![
+                button("RESUME", 0.0),
+                button("SAVE", 1.0),
+                button("SETTINGS", 2.0),
+                button("QUIT TO MENU", 3.0),
+            ],
+            confirm_buttons: vec![
+                button("SAVE AND QUIT", 1.0),
+                button("QUIT", 2.0),
+                button("CANCEL", 3.0),
+            ],
+            settings: SettingsScreen::new(textures),
+            state: PauseState::Main,
+        }
+    }
+
+    /// `unsaved_changes` decides whether quitting asks for confirmation first.
+    pub fn update(&mut self, input: &mut Input, settings: &mut Settings, unsaved_changes: bool) -> PauseAction {
+        match self.state {
+            PauseState::Settings => {
+                if self.settings.update(settings, input) {
+                    self.state = PauseState::Main;
+                    return PauseAction::SettingsChanged;
+                }
+                PauseAction::None
+            }
+            PauseState::ConfirmQuit => {
+                if input.is_pressed(Action::Pause) {
+                    self.state = PauseState::Main;
+                    return PauseAction::None;
+                }
+                for button in &mut self.confirm_buttons {
+                    if button.is_clicked() {
+                        match button.text.as_str() {
+                            "SAVE AND QUIT" => return PauseAction::SaveAndQuit,
+                            "QUIT" => return PauseAction::Quit,
+                            "CANCEL" => self.state = PauseState::Main,
+                            _ => {}
+                        }
+                    }
+                }
+                PauseAction::None
+            }
+            PauseState::Main => {
+                if input.is_pressed(Action::Pause) {
+                    return PauseAction::Resume;
+                }
+                for button in &mut self.buttons {
+                    if button.is_clicked() {
+                        match button.text.as_str() {
+                            "RESUME" => return PauseAction::Resume,
+                            "SAVE" => return PauseAction::Save,
+                            "SETTINGS" => self.state = PauseState::Settings,
+                            "QUIT TO MENU" if unsaved_changes => self.state = PauseState::ConfirmQuit,
+                            "QUIT TO MENU" => return PauseAction::Quit,
+                            _ => {}
+                        }
+                    }
+                }
+                PauseAction::None
+            }
+        }
+    }
+
+    pub fn draw(&self, input: &Input, settings: &Settings) {
+        set_default_camera();
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.5));
+
+        let (title, buttons) = match self.state {
+            PauseState::Settings => return self.settings.draw(settings, input),
+            PauseState::Main => ("PAUSED", &self.buttons),
+            PauseState::ConfirmQuit => ("Quit without saving?", &self.confirm_buttons),
+        };
+
+        let title_width = measure_text(title, None, 40, 1.0).width;
+        draw_text(title, (screen_width() - title_width) / 2.0, 100.0, 40.0, WHITE);
+
+        for button in buttons {
+            button.draw();
+        }
+    }
+}
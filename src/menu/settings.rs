@@ -0,0 +1,162 @@
+use macroquad::prelude::*;
+
+use crate::{input::input::Input, utils::settings::Settings};
+
+use super::{button::{button::Button, textures::ButtonTextures}, controls::ControlsScreen};
+
+const ROW_HEIGHT: f32 = 30.0;
+
+#[derive(Clone, Copy)]
+enum Setting {
+    WindowSize,
+    Fullscreen,
+    Vsync,
+    RenderDistance,
+    MasterVolume,
+    MusicVolume,
+    ShowDebug,
+    ClickToMove,
+    DayLength,
+}
+
+const ROWS: [(&str, Setting); 9] = [
+    ("Window size", Setting::WindowSize),
+    ("Fullscreen", Setting::Fullscreen),
+    ("VSync (restart)", Setting::Vsync),
+    ("Render distance", Setting::RenderDistance),
+    ("Master volume", Setting::MasterVolume),
+    ("Music volume", Setting::MusicVolume),
+    ("Debug info", Setting::ShowDebug),
+    ("Click to move", Setting::ClickToMove),
+    ("Day length (new worlds)", Setting::DayLength),
+];
+
+/// Settings screen shared by the main menu and the pause menu. Left click
+/// a value to increase it, right click to decrease it.
+pub struct SettingsScreen {
+    buttons: Vec<Button>,
+    controls: ControlsScreen,
+    in_controls: bool,
+}
+
+impl SettingsScreen {
+    pub fn new(textures: &ButtonTextures) -> Self {
+        let button_width = 200.0;
+        let button_y = 170.0 + ROWS.len() as f32 * ROW_HEIGHT;
+
+        Self {
+            buttons: vec![
+                Button::new("CONTROLS", vec2(screen_width() / 2.0 - button_width - 10.0, button_y), textures.clone()),
+                Button::new("BACK", vec2(screen_width() / 2.0 + 10.0, button_y), textures.clone()),
+            ],
+            controls: ControlsScreen::new(textures),
+            in_controls: false,
+        }
+    }
+
+    fn value_rect(row: usize) -> Rect {
+        Rect::new(screen_width() / 2.0 + 20.0, 150.0 + row as f32 * ROW_HEIGHT, 200.0, ROW_HEIGHT - 6.0)
+    }
+
+    fn value_text(settings: &Settings, setting: Setting) -> String {
+        let on_off = |value: bool| if value { "On" } else { "Off" }.to_owned();
+        match setting {
+            Setting::WindowSize => format!("{}x{}", settings.window_width, settings.window_height),
+            Setting::Fullscreen => on_off(settings.fullscreen),
+            Setting::Vsync => on_off(settings.vsync),
+            Setting::RenderDistance => format!("{} chunks", settings.render_distance),
+            Setting::MasterVolume => format!("{:.0}%", settings.master_volume * 100.0),
+            Setting::MusicVolume => format!("{:.0}%", settings.music_volume * 100.0),
+            Setting::ShowDebug => on_off(settings.show_debug),
+            Setting::ClickToMove => on_off(settings.click_to_move),
+            Setting::DayLength => format!("{:.0} min", settings.day_length / 60.0),
+        }
+    }
+
+    fn change(settings: &mut Settings, setting: Setting, step: i32) {
+        let volume = |value: f32| (value + step as f32 * 0.1).clamp(0.0, 1.0);
+        match setting {
+            Setting::WindowSize => {
+                settings.next_window_size(step);
+                settings.apply_window();
+            }
+            Setting::Fullscreen => {
+                settings.fullscreen = !settings.fullscreen;
+                settings.apply_window();
+            }
+            Setting::Vsync => settings.vsync = !settings.vsync,
+            Setting::RenderDistance => {
+                settings.render_distance = (settings.render_distance + step as f32).clamp(1.0, 8.0);
+            }
+            Setting::MasterVolume => settings.master_volume = volume(settings.master_volume),
+            Setting::MusicVolume => settings.music_volume = volume(settings.music_volume),
+            Setting::ShowDebug => settings.show_debug = !settings.show_debug,
+            Setting::ClickToMove => settings.click_to_move = !settings.click_to_move,
+            Setting::DayLength => {
+                settings.day_length = (settings.day_length + step as f64 * 120.0).clamp(120.0, 3600.0);
+            }
+        }
+    }
+
+    /// Returns `true` once the player leaves the screen. Settings are saved then.
+    pub fn update(&mut self, settings: &mut Settings, input: &mut Input) -> bool {
+        if self.in_controls {
+            if self.controls.update(input) {
+                self.in_controls = false;
+            }
+            return false;
+        }
+
+        let mouse = Vec2::from(mouse_position());
+        for (row, (_, setting)) in ROWS.iter().enumerate() {
+            if Self::value_rect(row).contains(mouse) {
+                if is_mouse_button_pressed(MouseButton::Left) {
+                    Self::change(settings, *setting, 1);
+                } else if is_mouse_button_pressed(MouseButton::Right) {
+                    Self::change(settings, *setting, -1);
+                }
+            }
+        }
+
+        for button in &mut self.buttons {
+            if button.is_clicked() {
+                match button.text.as_str() {
+                    "CONTROLS" => self.in_controls = true,
+                    "BACK" => {
+                        if let Err(e) = settings.save() {
+                            println!("Failed to save settings: {}", e);
+                        }
+                        return true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        false
+    }
+
+    pub fn draw(&self, settings: &Settings, input: &Input) {
+        if self.in_controls {
+            self.controls.draw(input);
+            return;
+        }
+
+        let title = "SETTINGS";
+        let title_width = measure_text(title, None, 40, 1.0).width;
+        draw_text(title, (screen_width() - title_width) / 2.0, 100.0, 40.0, WHITE);
+
+        let mouse = Vec2::from(mouse_position());
+        for (row, (label, setting)) in ROWS.iter().enumerate() {
+            let rect = Self::value_rect(row);
+            draw_text(label, screen_width() / 2.0 - 220.0, rect.y + 18.0, 20.0, WHITE);
+
+            let alpha = if rect.contains(mouse) { 0.6 } else { 0.4 };
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.0, 0.0, 0.0, alpha));
+            draw_text(&Self::value_text(settings, *setting), rect.x + 6.0, rect.y + 17.0, 18.0, WHITE);
+        }
+
+        for button in &self.buttons {
+            button.draw();
+        }
+    }
+}
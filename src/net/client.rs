@@ -0,0 +1,207 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::io;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use macroquad::math::Vec2;
+
+use crate::{game::{ecs::ecs::Entity, entity::player::player, simulation::simulation::Simulation, world::{clock::Clock, edit::WorldEdit}}, save::{mob::MobSave, vec2::Vec2Save}, utils::consts::{INTERPOLATION_DELAY, NET_SEND_INTERVAL, NET_TIMEOUT_SECS}};
+
+use super::{connection::Connection, protocol::{ClientMessage, MobId, PlayerId, PlayerState, ServerMessage, PROTOCOL_VERSION}};
+
+pub struct Welcome {
+    pub seed: u32,
+    pub spawn: Vec2,
+    pub clock: Clock,
+}
+
+/// Client side of a multiplayer session: feeds server data into the local
+/// `Simulation` and reports the local player back.
+pub struct NetClient {
+    connection: Connection,
+    pub id: PlayerId,
+    pending: Vec<ServerMessage>,
+    snapshots: HashMap<PlayerId, VecDeque<(Instant, PlayerState)>>,
+    /// Local entities of the server's mobs.
+    mobs: HashMap<MobId, Entity>,
+    mob_snapshots: HashMap<MobId, VecDeque<(Instant, Vec2)>>,
+    send_timer: f32,
+}
+
+impl NetClient {
+    /// Connects and waits for the server to accept the handshake.
+    pub fn connect(address: &str, name: &str, mods: Vec<String>) -> Result<(Self, Welcome), Box<dyn Error>> {
+        let timeout = Duration::from_secs(NET_TIMEOUT_SECS);
+        let addr = address.to_socket_addrs()?
+            .next()
+            .ok_or(format!("Could not resolve {}", address))?;
+        let mut connection = Connection::new(TcpStream::connect_timeout(&addr, timeout)?)?;
+
+        connection.send(&ClientMessage::Hello {
+            name: name.to_owned(),
+            protocol: PROTOCOL_VERSION,
+            mods,
+        })?;
+
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            connection.flush()?;
+            let mut messages = connection.receive::<ServerMessage>()?.into_iter();
+            while let Some(message) = messages.next() {
+                match message {
+                    ServerMessage::Welcome { id, seed, spawn, time, day_length } => {
+                        let client = Self {
+                            connection,
+                            id,
+                            pending: messages.collect(),
+                            snapshots: HashMap::new(),
+                            mobs: HashMap::new(),
+                            mob_snapshots: HashMap::new(),
+                            send_timer: 0.0,
+                        };
+                        return Ok((client, Welcome { seed, spawn: Vec2::from(spawn), clock: Clock::new(time, day_length) }));
+                    }
+                    ServerMessage::Rejected { reason } => return Err(reason.into()),
+                    _ => {}
+                }
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        Err("Timed out waiting for the server".into())
+    }
+
+    /// Applies everything the server sent since the last call.
+    pub fn poll(&mut self, sim: &mut Simulation) -> io::Result<()> {
+        self.connection.flush()?;
+        let mut messages = std::mem::take(&mut self.pending);
+        messages.extend(self.connection.receive::<ServerMessage>()?);
+
+        for message in messages {
+            match message {
+                ServerMessage::Chunk(save) => {
+                    sim.world.insert_chunk(save);
+                }
+                ServerMessage::PlayerJoined { id, name } => {
+                    println!("{} joined the game", name);
+                    let spawn = sim.player().spawn_point;
+                    sim.add_other(id, spawn);
+                }
+                ServerMessage::PlayerState { id, state } => {
+                    self.snapshots.entry(id).or_default().push_back((Instant::now(), state));
+                }
+                ServerMessage::PlayerLeft { id } => {
+                    sim.remove_other(id);
+                    self.snapshots.remove(&id);
+                }
+                ServerMessage::Edit(edit) => {
+                    sim.world.apply_edit(&edit);
+                }
+                ServerMessage::Mobs(mobs) => self.receive_mobs(sim, mobs),
+                ServerMessage::Time(time) => sim.world.clock.time = time,
+                ServerMessage::Weather(weather) => sim.world.weather = weather,
+                ServerMessage::Welcome { .. } | ServerMessage::Rejected { .. } => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Spawns mobs that are new to the client and despawns the ones the
+    /// server no longer has. Their movement is buffered like that of remote
+    /// players.
+    fn receive_mobs(&mut self, sim: &mut Simulation, mobs: Vec<(MobId, MobSave)>) {
+        let now = Instant::now();
+        let ids: HashSet<MobId> = mobs.iter().map(|(id, _)| *id).collect();
+        self.mobs.retain(|id, _| ids.contains(id));
+        self.mob_snapshots.retain(|id, _| ids.contains(id));
+
+        for (id, save) in mobs {
+            let (pos, hp) = (Vec2::from(save.pos.clone()), save.hp);
+            match self.mobs.get(&id).copied().filter(|entity| sim.world.ecs.is_alive(*entity)) {
+                Some(entity) => {
+                    if let Some(health) = sim.world.ecs.healths.get_mut(entity) {
+                        health.hp = hp;
+                    }
+                }
+                None => {
+                    // Its chunk was not loaded yet, or replaced with the mobs
+                    // it was saved with.
+                    let Some(entity) = sim.world.add_mob(save) else {
+                        continue;
+                    };
+                    self.mobs.insert(id, entity);
+                    self.mob_snapshots.remove(&id);
+                }
+            }
+            self.mob_snapshots.entry(id).or_default().push_back((now, pos));
+        }
+
+        let known: HashSet<Entity> = self.mobs.values().copied().collect();
+        sim.world.retain_mobs(|entity| known.contains(&entity));
+    }
+
+    /// Sends the local player's state at a fixed rate and moves remote
+    /// players and mobs along their received positions.
+    pub fn update(&mut self, dt: f32, sim: &mut Simulation) -> io::Result<()> {
+        self.send_timer += dt;
+        if self.send_timer >= NET_SEND_INTERVAL {
+            self.send_timer = 0.0;
+            if let Some(state) = player::network_state(&sim.world.ecs, sim.player) {
+                self.connection.send(&ClientMessage::PlayerState(state))?;
+            }
+        }
+
+        let render_time = Instant::now().checked_sub(Duration::from_secs_f32(INTERPOLATION_DELAY));
+        for (id, snapshots) in &mut self.snapshots {
+            let state = Self::interpolate(snapshots, render_time, |from, to, alpha| PlayerState {
+                pos: Vec2Save::from(Vec2::from(from.pos.clone()).lerp(Vec2::from(to.pos.clone()), alpha)),
+                ..to.clone()
+            });
+            if let (Some(entity), Some(state)) = (sim.others.get(id), state) {
+                player::apply_network_state(&mut sim.world.ecs, *entity, &state);
+            }
+        }
+        for (id, snapshots) in &mut self.mob_snapshots {
+            let pos = Self::interpolate(snapshots, render_time, |from, to, alpha| from.lerp(*to, alpha));
+            if let (Some(entity), Some(pos)) = (self.mobs.get(id), pos) {
+                sim.world.move_mob(*entity, pos, dt);
+            }
+        }
+
+        self.connection.flush()
+    }
+
+    pub fn send_edit(&mut self, edit: WorldEdit) -> io::Result<()> {
+        self.connection.send(&ClientMessage::Edit(edit))
+    }
+
+    /// State of a remote player or mob at `render_time`, blended by `lerp`
+    /// between the two snapshots around it. Older snapshots are dropped.
+    /// Without a `render_time`, right after the clock's origin, the newest
+    /// snapshot is used.
+    fn interpolate<T: Clone>(snapshots: &mut VecDeque<(Instant, T)>, render_time: Option<Instant>, lerp: impl Fn(&T, &T, f32) -> T) -> Option<T> {
+        let Some(render_time) = render_time else {
+            return snapshots.back().map(|(_, state)| state.clone());
+        };
+        while snapshots.len() > 2 && snapshots[1].0 <= render_time {
+            snapshots.pop_front();
+        }
+
+        let (from_time, from) = snapshots.front()?;
+        let Some((to_time, to)) = snapshots.get(1) else {
+            return Some(from.clone());
+        };
+        if render_time <= *from_time {
+            return Some(from.clone());
+        }
+
+        let span = to_time.duration_since(*from_time).as_secs_f32();
+        let alpha = if span > 0.0 {
+            (render_time.duration_since(*from_time).as_secs_f32() / span).min(1.0)
+        } else {
+            1.0
+        };
+        Some(lerp(from, to, alpha))
+    }
+}
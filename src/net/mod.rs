@@ -0,0 +1,3 @@
+pub mod client;
+pub mod connection;
+pub mod protocol;
\ No newline at end of file
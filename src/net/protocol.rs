@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{game::{entity::player::direction::Direction, world::{edit::WorldEdit, weather::Weather}}, save::{chunk::ChunkSave, mob::MobSave, vec2::Vec2Save}};
+
+pub const PROTOCOL_VERSION: u32 = 6;
+
+pub type PlayerId = u32;
+/// Id of a mob on the server, the same for as long as the mob lives.
+pub type MobId = u64;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerState {
+    pub pos: Vec2Save,
+    pub direction: Direction,
+    pub is_moving: bool,
+    pub animation_frame: i32,
+}
+
+/// Messages are sent as one JSON object per line.
+#[derive(Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// First message on a new connection. `mods` must match the server's.
+    Hello { name: String, protocol: u32, mods: Vec<String> },
+    PlayerState(PlayerState),
+    Edit(WorldEdit),
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum ServerMessage {
+    Welcome { id: PlayerId, seed: u32, spawn: Vec2Save, time: f64, day_length: f64 },
+    Rejected { reason: String },
+    Chunk(ChunkSave),
+    PlayerJoined { id: PlayerId, name: String },
+    PlayerState { id: PlayerId, state: PlayerState },
+    PlayerLeft { id: PlayerId },
+    Edit(WorldEdit),
+    /// Every mob in the chunks sent to the client. Mobs missing from it are
+    /// gone.
+    Mobs(Vec<(MobId, MobSave)>),
+    /// World clock, so clients don't drift from the server's day.
+    Time(f64),
+    Weather(Weather),
+}
@@ -1,6 +1,7 @@
+use macroquad::math::Vec2;
 use serde::{Deserialize, Serialize};
 
-use crate::game::entity::block::block::BlockType;
+use crate::game::{ecs::ecs::{Ecs, Entity}, entity::block::block::BlockType, world::generator::biome::Biome};
 
 use super::vec2::Vec2Save;
 
@@ -8,4 +9,27 @@ use super::vec2::Vec2Save;
 pub struct BlockSave {
     pub pos: Vec2Save,
     pub block_type: BlockType,
-}
\ No newline at end of file
+    /// Growth of crops, unused by other blocks.
+    #[serde(default)]
+    pub growth: f32,
+}
+
+impl BlockSave {
+    /// Returns `None` if `entity` isn't a block.
+    pub fn from_entity(ecs: &Ecs, entity: Entity) -> Option<Self> {
+        Some(BlockSave {
+            pos: Vec2Save::from(ecs.transforms.get(entity)?.pos),
+            block_type: ecs.blocks.get(entity)?.clone(),
+            growth: ecs.growths.get(entity).map(|growth| growth.seconds).unwrap_or(0.0),
+        })
+    }
+
+    /// `biome` is the biome of the tile the block stands on.
+    pub fn spawn(self, ecs: &mut Ecs, biome: Biome) -> Entity {
+        let entity = self.block_type.spawn(ecs, Vec2::from(self.pos), biome);
+        if let Some(growth) = ecs.growths.get_mut(entity) {
+            growth.seconds = self.growth;
+        }
+        entity
+    }
+}
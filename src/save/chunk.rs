@@ -1,12 +1,55 @@
+use macroquad::math::Vec2;
 use serde::{Deserialize, Serialize};
 
-use super::{block::BlockSave, tile::TileSave};
+use crate::{game::{ecs::ecs::Ecs, entity::mob::registry::MobRegistry, world::{chunk::chunk::Chunk, generator::biome::Biome, tile::tile::Tile, world::World}}, utils::consts::CHUNK_SIZE};
 
+use super::{block::BlockSave, mob::MobSave, tile::TileSave};
 
 
-#[derive(Serialize, Deserialize)]
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ChunkSave {
     pub pos: (usize, usize),
     pub tiles: Vec<TileSave>,
     pub blocks: Vec<BlockSave>,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub mobs: Vec<MobSave>,
+    /// Game time when the chunk was unloaded or saved, to catch up on
+    /// crop growth when it is loaded again.
+    #[serde(default)]
+    pub saved_at: f64,
+}
+
+impl ChunkSave {
+    /// Saves the chunk's tiles and the components of its entities.
+    pub fn new(chunk: &Chunk, ecs: &Ecs) -> Self {
+        ChunkSave {
+            pos: chunk.pos,
+            tiles: chunk.tiles.iter().map(TileSave::from).collect(),
+            blocks: chunk.blocks.iter().filter_map(|entity| BlockSave::from_entity(ecs, *entity)).collect(),
+            mobs: chunk.mobs.iter().filter_map(|entity| MobSave::from_entity(ecs, *entity)).collect(),
+            saved_at: 0.0,
+        }
+    }
+
+    /// Spawns the saved blocks and mobs into `ecs`.
+    pub fn restore(self, ecs: &mut Ecs, mob_types: &MobRegistry) -> Chunk {
+        let mut chunk = Chunk::new((self.pos.0, self.pos.1));
+        chunk.tiles = self.tiles.into_iter()
+            .map(Tile::from)
+            .collect();
+
+        chunk.blocks = self.blocks.into_iter()
+            .map(|block_save| {
+                let tile_biome = World::tile_coords_at(Vec2::from(block_save.pos.clone()))
+                    .and_then(|(x, y)| chunk.tile(x % CHUNK_SIZE, y % CHUNK_SIZE))
+                    .map(|t| t.biome.clone())
+                    .unwrap_or(Biome::Forest);
+
+                block_save.spawn(ecs, tile_biome)
+            })
+            .collect();
+        chunk.mobs = self.mobs.into_iter().filter_map(|mob_save| mob_save.spawn(ecs, mob_types)).collect();
+        chunk
+    }
+}
@@ -0,0 +1,34 @@
+use macroquad::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{ecs::ecs::{Ecs, Entity}, entity::mob::{mob, registry::MobRegistry}};
+
+use super::vec2::Vec2Save;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MobSave {
+    pub kind: String,
+    pub pos: Vec2Save,
+    pub hp: i32,
+}
+
+impl MobSave {
+    /// Returns `None` if `entity` isn't a mob.
+    pub fn from_entity(ecs: &Ecs, entity: Entity) -> Option<Self> {
+        Some(MobSave {
+            kind: ecs.mobs.get(entity)?.kind.clone(),
+            pos: Vec2Save::from(ecs.transforms.get(entity)?.pos),
+            hp: ecs.healths.get(entity)?.hp,
+        })
+    }
+
+    /// Returns `None` for mobs of unknown types, e.g. from a removed mod.
+    pub fn spawn(self, ecs: &mut Ecs, mob_types: &MobRegistry) -> Option<Entity> {
+        let mob_type = mob_types.get(&self.kind)?;
+        let entity = mob::spawn(ecs, mob_type, Vec2::from(self.pos));
+        if let Some(health) = ecs.healths.get_mut(entity) {
+            health.hp = self.hp;
+        }
+        Some(entity)
+    }
+}
@@ -1,5 +1,6 @@
 pub mod block;
 pub mod chunk;
+pub mod mob;
 pub mod tile;
 pub mod vec2;
-pub mod world;
\ No newline at end of file
+pub mod world;
\ No newline at end of file
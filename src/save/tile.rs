@@ -10,16 +10,19 @@ pub struct TileSave {
     pos: Vec2Save,
     biome: Biome,
     state: TileState,
+    /// Missing in saves from before seasons. Estimated from the biome then.
+    #[serde(default)]
+    temperature: Option<f32>,
 }
 
 impl From<TileSave> for Tile {
     fn from(save: TileSave) -> Self {
         Self {
             pos: Vec2::from(save.pos),
-            biome: save.biome,
+            biome: save.biome.clone(),
             state: save.state,
             size: 32.0,
-            textures: None,
+            temperature: save.temperature.unwrap_or_else(|| save.biome.typical_temperature()),
         }
     }
 }
@@ -30,6 +33,7 @@ impl From<&Tile> for TileSave {
             pos: Vec2Save::from(tile.pos),
             biome: tile.biome.clone(),
             state: tile.state.clone(),
+            temperature: Some(tile.temperature),
         }
     }
-}
\ No newline at end of file
+}
\ No newline at end of file
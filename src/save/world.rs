@@ -1,10 +1,29 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{game::{item::inventory::Inventory, world::weather::Weather}, utils::consts::DAY_LENGTH};
+
 use super::{chunk::ChunkSave, vec2::Vec2Save};
 
 #[derive(Serialize, Deserialize)]
 pub struct WorldSave {
     pub seed: u32,
     pub player_pos: Vec2Save,
+    /// Missing in saves from before the inventory existed.
+    #[serde(default)]
+    pub inventory: Option<Inventory>,
+    #[serde(default)]
+    pub time: f64,
+    #[serde(default = "default_day_length")]
+    pub day_length: f64,
+    #[serde(default)]
+    pub weather: Weather,
+    #[serde(default)]
+    pub hp: Option<i32>,
+    #[serde(default)]
+    pub spawn_point: Option<Vec2Save>,
     pub chunks: Vec<ChunkSave>,
-}
\ No newline at end of file
+}
+
+fn default_day_length() -> f64 {
+    DAY_LENGTH
+}
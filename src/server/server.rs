@@ -0,0 +1,372 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::io::{self, BufRead};
+use std::net::{SocketAddr, TcpListener};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{game::{entity::player::player, simulation::{input::InputSnapshot, simulation::Simulation}, world::{chunk::chunk::ChunkPos, clock::Clock, world::World}}, net::{connection::Connection, protocol::{ClientMessage, PlayerId, ServerMessage, PROTOCOL_VERSION}}, save::{chunk::ChunkSave, mob::MobSave, vec2::Vec2Save}, utils::{consts::{DAY_LENGTH, FIXED_TIMESTEP, NET_SEND_INTERVAL, NET_VIEW_DISTANCE, SERVER_AUTOSAVE_SECS, SERVER_PORT}, generate_seed::generate_seed, mod_loader::ModLoader}};
+
+pub struct ServerConfig {
+    pub port: u16,
+    pub world_file: String,
+    pub seed: Option<u32>,
+    /// Only used when a new world is created.
+    pub day_length: f64,
+}
+
+impl ServerConfig {
+    /// Reads `--port`, `--world`, `--seed` and `--day-length` from the command line.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Self, Box<dyn Error>> {
+        let mut config = Self {
+            port: SERVER_PORT,
+            world_file: "world".to_owned(),
+            seed: None,
+            day_length: DAY_LENGTH,
+        };
+
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+            match arg.as_str() {
+                "--port" => config.port = value()?.parse()?,
+                "--world" => config.world_file = value()?,
+                "--seed" => config.seed = Some(value()?.parse()?),
+                "--day-length" => config.day_length = value()?.parse()?,
+                _ => return Err(format!("Unknown argument: {}", arg).into()),
+            }
+        }
+        Ok(config)
+    }
+}
+
+pub enum ServerCommand {
+    Save,
+    Stop,
+}
+
+struct RemoteClient {
+    connection: Connection,
+    addr: SocketAddr,
+    /// Set once the handshake succeeded.
+    name: Option<String>,
+    sent_chunks: HashSet<ChunkPos>,
+}
+
+/// Runs the world without a window and accepts players over TCP.
+pub struct Server {
+    simulation: Simulation,
+    listener: TcpListener,
+    clients: HashMap<PlayerId, RemoteClient>,
+    next_id: PlayerId,
+    mods: Vec<String>,
+    world_file: String,
+    commands: mpsc::Receiver<ServerCommand>,
+    last_save: Instant,
+    mob_timer: f32,
+}
+
+impl Server {
+    /// Uses the same content of `mods` as clients, which have to have the
+    /// same mods to join.
+    pub fn new(config: ServerConfig, mods: &ModLoader) -> Result<Self, Box<dyn Error>> {
+        let custom_biomes = mods.get_biomes().to_vec();
+        let mut simulation = if Path::new(&config.world_file).exists() {
+            println!("Loading world from {}", config.world_file);
+            Simulation::load(&config.world_file, custom_biomes)?
+        } else {
+            let seed = config.seed.unwrap_or_else(generate_seed);
+            println!("Creating new world with seed {}", seed);
+            let mut simulation = Simulation::new(seed, custom_biomes);
+            simulation.world.clock = Clock::new(0.0, config.day_length);
+            simulation
+        };
+
+        simulation.add_mod_content(mods.get_tiles(), mods.get_recipes(), mods.get_mobs());
+        simulation.local_player = false;
+
+        let listener = TcpListener::bind(("0.0.0.0", config.port))?;
+        listener.set_nonblocking(true)?;
+        println!("Listening on port {}", config.port);
+
+        Ok(Self {
+            simulation,
+            listener,
+            clients: HashMap::new(),
+            next_id: 1,
+            mods: mods.get_mod_ids(),
+            world_file: config.world_file,
+            commands: Self::spawn_console(),
+            last_save: Instant::now(),
+            mob_timer: 0.0,
+        })
+    }
+
+    fn spawn_console() -> mpsc::Receiver<ServerCommand> {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines().map_while(Result::ok) {
+                let command = match line.trim() {
+                    "save" => ServerCommand::Save,
+                    "stop" => ServerCommand::Stop,
+                    other => {
+                        println!("Unknown command: {} (available: save, stop)", other);
+                        continue;
+                    }
+                };
+                if sender.send(command).is_err() {
+                    break;
+                }
+            }
+        });
+        receiver
+    }
+
+    pub fn run(&mut self) {
+        let tick = Duration::from_secs_f32(FIXED_TIMESTEP);
+        let input = InputSnapshot::default();
+
+        loop {
+            let started = Instant::now();
+
+            self.accept_clients();
+            self.handle_messages();
+            self.simulation.stream_chunks(NET_VIEW_DISTANCE);
+            self.simulation.step(FIXED_TIMESTEP, &input);
+            self.send_chunks();
+            self.send_world_state();
+            self.flush_clients();
+
+            match self.commands.try_recv() {
+                Ok(ServerCommand::Save) => self.save(),
+                Ok(ServerCommand::Stop) => {
+                    self.save();
+                    println!("Server stopped");
+                    return;
+                }
+                Err(_) => {}
+            }
+
+            if self.last_save.elapsed().as_secs() >= SERVER_AUTOSAVE_SECS {
+                self.save();
+            }
+
+            if let Some(remaining) = tick.checked_sub(started.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+    }
+
+    fn accept_clients(&mut self) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, addr)) => {
+                    println!("Client connected: {}", addr);
+                    match Connection::new(stream) {
+                        Ok(connection) => {
+                            self.clients.insert(self.next_id, RemoteClient {
+                                connection,
+                                addr,
+                                name: None,
+                                sent_chunks: HashSet::new(),
+                            });
+                            self.next_id += 1;
+                        }
+                        Err(e) => println!("Failed to set up connection with {}: {}", addr, e),
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    println!("Failed to accept client: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn handle_messages(&mut self) {
+        let mut disconnected = Vec::new();
+        let ids: Vec<PlayerId> = self.clients.keys().copied().collect();
+
+        for id in ids {
+            let messages = match self.clients.get_mut(&id).map(|client| client.connection.receive::<ClientMessage>()) {
+                Some(Ok(messages)) => messages,
+                Some(Err(_)) => {
+                    disconnected.push(id);
+                    continue;
+                }
+                None => continue,
+            };
+
+            for message in messages {
+                match message {
+                    ClientMessage::Hello { name, protocol, mods } => {
+                        if let Err(reason) = self.check_hello(protocol, &mods) {
+                            self.send(id, &ServerMessage::Rejected { reason });
+                            if let Some(client) = self.clients.get_mut(&id) {
+                                let _ = client.connection.flush();
+                            }
+                            disconnected.push(id);
+                            break;
+                        }
+                        self.join(id, name);
+                    }
+                    ClientMessage::PlayerState(state) => {
+                        if let Some(entity) = self.simulation.others.get(&id) {
+                            player::apply_network_state(&mut self.simulation.world.ecs, *entity, &state);
+                            self.broadcast(id, &ServerMessage::PlayerState { id, state });
+                        }
+                    }
+                    ClientMessage::Edit(edit) => {
+                        let Some(entity) = self.simulation.others.get(&id).copied() else {
+                            continue;
+                        };
+                        if !self.simulation.can_edit(entity, &edit) {
+                            // Sending the chunk again undoes the edit on the client.
+                            if let (Some(client), Some(pos)) = (self.clients.get_mut(&id), World::chunk_pos_at(edit.pos())) {
+                                println!("Rejected edit out of reach from {}", client.addr);
+                                client.sent_chunks.remove(&pos);
+                            }
+                            continue;
+                        }
+                        if self.simulation.apply_edit(&edit) {
+                            self.broadcast(id, &ServerMessage::Edit(edit));
+                        }
+                    }
+                }
+            }
+        }
+
+        for id in disconnected {
+            self.disconnect(id);
+        }
+    }
+
+    fn check_hello(&self, protocol: u32, mods: &[String]) -> Result<(), String> {
+        if protocol != PROTOCOL_VERSION {
+            return Err(format!("Protocol version {} is not supported, server uses {}", protocol, PROTOCOL_VERSION));
+        }
+        if mods != self.mods.as_slice() {
+            return Err(format!("Mod list does not match the server: {:?}", self.mods));
+        }
+        Ok(())
+    }
+
+    fn join(&mut self, id: PlayerId, name: String) {
+        let spawn = self.simulation.player_transform().pos;
+        self.send(id, &ServerMessage::Welcome {
+            id,
+            seed: self.simulation.generator.seed,
+            spawn: Vec2Save::from(spawn),
+            time: self.simulation.world.clock.time,
+            day_length: self.simulation.world.clock.day_length,
+        });
+
+        let existing: Vec<(PlayerId, String)> = self.clients.iter()
+            .filter(|(other, _)| **other != id)
+            .filter_map(|(other, client)| client.name.clone().map(|name| (*other, name)))
+            .collect();
+        for (other, other_name) in existing {
+            self.send(id, &ServerMessage::PlayerJoined { id: other, name: other_name });
+            let state = self.simulation.others.get(&other)
+                .and_then(|entity| player::network_state(&self.simulation.world.ecs, *entity));
+            if let Some(state) = state {
+                self.send(id, &ServerMessage::PlayerState { id: other, state });
+            }
+        }
+
+        self.simulation.add_other(id, spawn);
+        self.broadcast(id, &ServerMessage::PlayerJoined { id, name: name.clone() });
+
+        if let Some(client) = self.clients.get_mut(&id) {
+            println!("{} joined from {}", name, client.addr);
+            client.name = Some(name);
+        }
+    }
+
+    fn disconnect(&mut self, id: PlayerId) {
+        if let Some(client) = self.clients.remove(&id) {
+            println!("Client disconnected: {}", client.addr);
+            if client.name.is_some() {
+                self.simulation.remove_other(id);
+                self.broadcast(id, &ServerMessage::PlayerLeft { id });
+            }
+        }
+    }
+
+    /// Sends every loaded chunk near a player that their client does not have yet.
+    fn send_chunks(&mut self) {
+        for (id, client) in &mut self.clients {
+            let Some(player_chunk) = self.simulation.others.get(id)
+                .and_then(|entity| self.simulation.world.ecs.transforms.get(*entity))
+                .and_then(|transform| World::chunk_pos_at(transform.pos)) else {
+                continue;
+            };
+            let in_range = |pos: &ChunkPos| {
+                let dx = pos.0 as f32 - player_chunk.0 as f32;
+                let dy = pos.1 as f32 - player_chunk.1 as f32;
+                (dx * dx + dy * dy).sqrt() <= NET_VIEW_DISTANCE
+            };
+
+            client.sent_chunks.retain(in_range);
+            for chunk in self.simulation.world.chunks.values() {
+                if in_range(&chunk.pos) && client.sent_chunks.insert(chunk.pos) {
+                    let _ = client.connection.send(&ServerMessage::Chunk(ChunkSave::new(chunk, &self.simulation.world.ecs)));
+                }
+            }
+        }
+    }
+
+    /// Sends mobs, the world clock and the weather at a fixed rate.
+    fn send_world_state(&mut self) {
+        self.mob_timer += FIXED_TIMESTEP;
+        if self.mob_timer < NET_SEND_INTERVAL {
+            return;
+        }
+        self.mob_timer = 0.0;
+
+        for client in self.clients.values_mut().filter(|client| client.name.is_some()) {
+            let mobs = client.sent_chunks.iter()
+                .filter_map(|pos| self.simulation.world.chunks.get(pos))
+                .flat_map(|chunk| chunk.mobs.iter().filter_map(|entity| MobSave::from_entity(&self.simulation.world.ecs, *entity).map(|mob| (entity.to_bits(), mob))))
+                .collect();
+            let _ = client.connection.send(&ServerMessage::Mobs(mobs));
+            let _ = client.connection.send(&ServerMessage::Time(self.simulation.world.clock.time));
+            let _ = client.connection.send(&ServerMessage::Weather(self.simulation.world.weather));
+        }
+    }
+
+    fn flush_clients(&mut self) {
+        let failed: Vec<PlayerId> = self.clients.iter_mut()
+            .filter_map(|(id, client)| client.connection.flush().err().map(|_| *id))
+            .collect();
+        for id in failed {
+            self.disconnect(id);
+        }
+    }
+
+    fn send(&mut self, id: PlayerId, message: &ServerMessage) {
+        if let Some(client) = self.clients.get_mut(&id) {
+            let _ = client.connection.send(message);
+        }
+    }
+
+    /// Sends `message` to every joined client except `from`.
+    fn broadcast(&mut self, from: PlayerId, message: &ServerMessage) {
+        for (id, client) in &mut self.clients {
+            if *id != from && client.name.is_some() {
+                let _ = client.connection.send(message);
+            }
+        }
+    }
+
+    fn save(&mut self) {
+        match self.simulation.save(&self.world_file) {
+            Ok(()) => println!("World saved to {}", self.world_file),
+            Err(e) => println!("Failed to save world: {}", e),
+        }
+        self.last_save = Instant::now();
+    }
+}
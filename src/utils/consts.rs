@@ -11,13 +11,108 @@ pub const WORLD_SIZE: usize = 1024;
 pub const MENU_WORLD_SIZE: usize = 48;
 pub const TILE_SIZE: f32 = 32.0;
 pub const CHUNK_PIXELS: f32 = CHUNK_SIZE as f32 * TILE_SIZE;
-pub const BLOCK_CACHE: usize = 32;
+pub const FIXED_TIMESTEP: f32 = 1.0 / 60.0;
+pub const MAX_FRAME_TIME: f32 = 0.25;
+pub const PLAYER_SPEED: f32 = 120.0;
+/// Two hit points per heart.
+pub const PLAYER_MAX_HP: i32 = 20;
+/// Time after taking damage in which the player can't be hurt again.
+pub const PLAYER_INVULNERABILITY: f32 = 0.75;
+/// Seconds the player can stay in deep water before drowning.
+pub const PLAYER_AIR: f32 = 5.0;
+pub const DROWN_DAMAGE: i32 = 1;
+pub const CACTUS_DAMAGE: i32 = 1;
+/// Seconds without damage before health regenerates.
+pub const REGEN_DELAY: f32 = 5.0;
+pub const REGEN_INTERVAL: f32 = 2.0;
+/// Health restored by eating meat.
+pub const MEAT_HEAL: i32 = 4;
+/// Seconds of game time from one sunrise to the next.
+pub const DAY_LENGTH: f64 = 600.0;
+/// Part of the day after which it is night.
+pub const NIGHT_START: f64 = 0.6;
+/// Brightest light level. Light loses one level per tile it travels.
+pub const MAX_LIGHT: u8 = 15;
+pub const TORCH_LIGHT: u8 = 12;
+pub const CAMPFIRE_LIGHT: u8 = 15;
+/// Days in each season.
+pub const SEASON_DAYS: u64 = 4;
+/// Tiles colder than this are snowy, after the season's offset.
+pub const SNOW_TEMPERATURE: f64 = 0.3;
+/// Tiles moister than this get rain when the weather turns bad.
+pub const RAIN_MOISTURE: f64 = 0.45;
+/// Chance that the weather turns bad whenever it changes.
+pub const STORM_CHANCE: f32 = 0.35;
+/// Seconds between weather changes.
+pub const WEATHER_MIN_TIME: f32 = 60.0;
+pub const WEATHER_MAX_TIME: f32 = 240.0;
+/// How fast weather fades in and out, in intensity per second.
+pub const WEATHER_FADE: f32 = 0.1;
+/// Raindrops or snowflakes on screen at full intensity.
+pub const WEATHER_PARTICLES: usize = 300;
+/// Crops grow this much slower at night.
+pub const CROP_NIGHT_GROWTH: f32 = 0.5;
+pub const MOB_SPAWN_INTERVAL: f32 = 2.0;
+pub const MAX_MOBS: usize = 24;
+pub const MOBS_PER_CHUNK: usize = 2;
+/// Mobs don't spawn closer than this to a player, so they don't appear in view.
+pub const MOB_SPAWN_DISTANCE: f32 = 10.0 * TILE_SIZE;
+/// How close a player has to be for a hostile mob to chase them.
+pub const MOB_CHASE_DISTANCE: f32 = 8.0 * TILE_SIZE;
+/// Most tiles a path search looks at before giving up.
+pub const PATH_MAX_NODES: usize = 2048;
+/// Seconds between path searches of a mob chasing a player.
+pub const PATH_REFRESH: f32 = 1.0;
+/// How far from the player's center tiles and blocks can be edited.
+pub const REACH_DISTANCE: f32 = 4.0 * TILE_SIZE;
+/// Extra reach the server allows for edits from clients, whose player it
+/// only knows from their last update.
+pub const NET_REACH_SLACK: f32 = 1.5 * TILE_SIZE;
+pub const INVENTORY_SIZE: usize = 27;
+pub const HOTBAR_SIZE: usize = 9;
+pub const MAX_STACK: u32 = 64;
+pub const CROP_STAGES: u32 = 4;
+pub const CROP_STAGE_SECONDS: f32 = 60.0;
+/// Growth speed multiplier for crops with water within `CROP_WATER_RADIUS` tiles.
+pub const CROP_WATER_BONUS: f32 = 2.0;
+pub const CROP_WATER_RADIUS: i32 = 3;
+pub const CROP_TICK: f32 = 1.0;
+/// Chance of finding seeds when tilling grass.
+pub const SEED_CHANCE: f32 = 0.25;
+pub const SERVER_PORT: u16 = 7777;
+/// Where single player worlds are saved.
+pub const SAVE_FILE: &str = "world";
+pub const CONTROLS_FILE: &str = "controls.json";
+pub const SETTINGS_FILE: &str = "settings.json";
+pub const RECIPES_FILE: &str = "assets/recipes.json";
+pub const PARTICLES_FILE: &str = "assets/particles.json";
+/// Size of the particle pool. Bursts are cut short while it is full.
+pub const MAX_PARTICLES: usize = 2048;
+/// Frames in the water and tree canopy sprite sheets.
+pub const WATER_FRAMES: u32 = 4;
+pub const WATER_FRAME_TIME: f32 = 0.3;
+pub const TREE_FRAMES: u32 = 4;
+pub const TREE_FRAME_TIME: f32 = 0.5;
+pub const MOBS_FILE: &str = "assets/mobs.json";
+pub const GAMEPAD_DEADZONE: f32 = 0.2;
+pub const SERVER_AUTOSAVE_SECS: u64 = 60;
+pub const NET_VIEW_DISTANCE: f32 = 4.0;
+pub const NET_SEND_INTERVAL: f32 = 0.05;
+pub const NET_TIMEOUT_SECS: u64 = 5;
+pub const INTERPOLATION_DELAY: f32 = 0.1;
+pub const BASE_ZOOM: f32 = 0.0033334 / 3.0;
+pub const MIN_ZOOM: f32 = 0.75;
+pub const MAX_ZOOM: f32 = 3.0;
+pub const ZOOM_STEP: f32 = 0.25;
+pub const ZOOM_SPEED: f32 = 10.0;
+pub const CAMERA_FOLLOW_SPEED: f32 = 8.0;
+pub const CAMERA_DEADZONE: f32 = 24.0;
 lazy_static! {
     pub static ref DEFAULT_TILE: Tile = Tile {
     state: TileState::Grass,
     pos: Vec2::new(0.0, 0.0),
     biome: Biome::Plains,
     size: 32.0,
-    textures: None,
+    temperature: 0.5,
     };
-}
\ No newline at end of file
+}
\ No newline at end of file
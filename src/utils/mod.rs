@@ -1,4 +1,5 @@
 pub mod mod_loader;
 pub mod system;
+pub mod settings;
 pub mod consts;
-pub mod generate_seed;
\ No newline at end of file
+pub mod generate_seed;
\ No newline at end of file
@@ -10,6 +10,9 @@ pub struct ModLoader {
     mods_path: PathBuf,
     biomes: Vec<BiomeMod>,
     tiles: Vec<TileMod>,
+    recipes: Vec<RecipeMod>,
+    mobs: Vec<MobMod>,
+    emitters: Vec<EmitterMod>,
 }
 
 impl ModLoader {
@@ -18,13 +21,20 @@ impl ModLoader {
             mods: Vec::new(),
             mods_path: PathBuf::from("mods"),
             biomes: Vec::new(),
-            tiles: Vec::new()
+            tiles: Vec::new(),
+            recipes: Vec::new(),
+            mobs: Vec::new(),
+            emitters: Vec::new(),
         }
     }
 
     pub fn load_mods(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         self.mods.clear();
         self.biomes.clear();
+        self.tiles.clear();
+        self.recipes.clear();
+        self.mobs.clear();
+        self.emitters.clear();
 
         if !self.mods_path.exists() {
             fs::create_dir(&self.mods_path)?;
@@ -55,6 +65,9 @@ impl ModLoader {
             config: ModConfig,
             biomes: Option<Vec<BiomeMod>>,
             tiles: Option<Vec<TileMod>>,
+            recipes: Option<Vec<RecipeMod>>,
+            mobs: Option<Vec<MobMod>>,
+            emitters: Option<Vec<EmitterMod>>,
         }
         
         let mod_data: ModData = serde_json::from_str(&json_content)?;
@@ -75,6 +88,27 @@ impl ModLoader {
             }
         }
         
+        if let Some(mod_recipes) = mod_data.recipes {
+            for recipe in mod_recipes {
+                println!("Loading recipe: {}", recipe.id);
+                self.recipes.push(recipe);
+            }
+        }
+        
+        if let Some(mod_mobs) = mod_data.mobs {
+            for mob in mod_mobs {
+                println!("Loading mob: {}", mob.name);
+                self.mobs.push(mob);
+            }
+        }
+        
+        if let Some(mod_emitters) = mod_data.emitters {
+            for emitter in mod_emitters {
+                println!("Loading particle emitter: {}", emitter.id);
+                self.emitters.push(emitter);
+            }
+        }
+        
         self.mods.push(mod_data.config);
         Ok(())
     }
@@ -86,4 +120,23 @@ impl ModLoader {
     pub fn get_tiles(&self) -> &[TileMod] {
         &self.tiles
     }
-}
\ No newline at end of file
+
+    pub fn get_recipes(&self) -> &[RecipeMod] {
+        &self.recipes
+    }
+
+    pub fn get_mobs(&self) -> &[MobMod] {
+        &self.mobs
+    }
+
+    pub fn get_emitters(&self) -> &[EmitterMod] {
+        &self.emitters
+    }
+
+    /// `id@version` of every loaded mod, compared when joining a server.
+    pub fn get_mod_ids(&self) -> Vec<String> {
+        self.mods.iter()
+            .map(|config| format!("{}@{}", config.id, config.version))
+            .collect()
+    }
+}
\ No newline at end of file
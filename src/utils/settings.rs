@@ -0,0 +1,81 @@
+use std::{error::Error, fs};
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::consts::{DAY_LENGTH, RENDER_DISTANCE, SETTINGS_FILE};
+
+pub const WINDOW_SIZES: [(i32, i32); 5] = [(800, 600), (1024, 768), (1280, 720), (1600, 900), (1920, 1080)];
+
+/// Player settings stored in `settings.json`. Missing fields use defaults.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window_width: i32,
+    pub window_height: i32,
+    pub fullscreen: bool,
+    /// Only applied on startup.
+    pub vsync: bool,
+    /// Minimum number of chunks loaded around the player.
+    pub render_distance: f32,
+    /// Saved for when the game plays sound; nothing reads it yet.
+    pub master_volume: f32,
+    /// Saved for when the game plays sound; nothing reads it yet.
+    pub music_volume: f32,
+    pub show_debug: bool,
+    /// Clicking a tile out of reach walks there.
+    pub click_to_move: bool,
+    /// Seconds from one sunrise to the next in newly created worlds.
+    pub day_length: f64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            window_width: 800,
+            window_height: 600,
+            fullscreen: false,
+            vsync: true,
+            render_distance: RENDER_DISTANCE,
+            master_volume: 1.0,
+            music_volume: 1.0,
+            show_debug: false,
+            click_to_move: false,
+            day_length: DAY_LENGTH,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        match fs::read_to_string(SETTINGS_FILE) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                println!("Failed to parse {}: {}, using default settings", SETTINGS_FILE, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(SETTINGS_FILE, json)?;
+        Ok(())
+    }
+
+    /// Applies the window settings to the running window.
+    pub fn apply_window(&self) {
+        set_fullscreen(self.fullscreen);
+        if !self.fullscreen {
+            request_new_screen_size(self.window_width as f32, self.window_height as f32);
+        }
+    }
+
+    pub fn next_window_size(&mut self, step: i32) {
+        let current = WINDOW_SIZES.iter()
+            .position(|size| *size == (self.window_width, self.window_height))
+            .unwrap_or(0) as i32;
+        let next = (current + step).rem_euclid(WINDOW_SIZES.len() as i32) as usize;
+        (self.window_width, self.window_height) = WINDOW_SIZES[next];
+    }
+}